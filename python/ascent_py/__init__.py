@@ -1,20 +1,27 @@
 """Python bindings for Ascent Datalog"""
 
 from .ascent_py import DeductiveDatabase as _DeductiveDatabase
-from typing import List, Tuple
+from .ascent_py import Fact
+from typing import List, Optional, Tuple
 import itertools
 
 
+def _coord(v) -> float:
+    """A coordinate as a Python int or float, as the database expects"""
+    return float(v) if isinstance(v, float) else int(v)
+
+
 class DeductiveDatabase:
     """High-level Python wrapper for geometric deduction queries"""
 
-    def __init__(self):
-        self._prog = _DeductiveDatabase()
+    def __init__(self, epsilon: Optional[float] = None):
+        """Integer points by default; with an epsilon, floating-point points compared within it"""
+        self._prog = _DeductiveDatabase(epsilon)
 
     # Input methods
-    def add_point(self, x: int = 0, y: int = 0, name: str = ""):
+    def add_point(self, x: float = 0, y: float = 0, name: str = ""):
         """Add a point to the geometry with coordinates"""
-        self._prog.add_point(int(x), int(y), name)
+        self._prog.add_point(_coord(x), _coord(y), name)
 
     def add_col(self, a: str, b: str, c: str):
         """Add collinearity fact: points a, b, c are collinear"""
@@ -48,6 +55,11 @@ class DeductiveDatabase:
         """Add midpoint fact: M is the midpoint of segment AB"""
         self._prog.add_midp(m, a, b)
 
+    def add_diameter(self, o: str, a: str, b: str):
+        """Add diameter fact: AB is a diameter of a circle centered O (O is the midpoint of AB);
+        a point P is on that circle given cong(O, A, O, P)"""
+        self._prog.add_diameter(o, a, b)
+
     def add_contri1(self, a: str, b: str, c: str, d: str, e: str, f: str):
         """Add congruent triangles fact (same orientation): triangle ABC ≅ triangle DEF"""
         self._prog.add_contri1(a, b, c, d, e, f)
@@ -74,10 +86,169 @@ class DeductiveDatabase:
         """Add constant angle fact: ∠ABC = mπ/n"""
         self._prog.add_aconst(a, b, c, m, n)
 
+    def add_lconst(self, a: str, b: str, m: int, n: int):
+        """Add constant length fact: |AB| = m/n"""
+        self._prog.add_lconst(a, b, m, n)
+
+    def add_rconst(self, a: str, b: str, c: str, d: str, m: int, n: int):
+        """Add constant ratio fact: |AB| / |CD| = m/n"""
+        self._prog.add_rconst(a, b, c, d, m, n)
+
+    def add_tangent(self, a: str, b: str, o: str, p: str):
+        """Add tangency fact: line AB is tangent at P to the circle centered O"""
+        self._prog.add_tangent(a, b, o, p)
+
+    # Bulk input methods - one call for many facts, skipping duplicates
+    def add_points(self, points: List[Tuple[float, float, str]]):
+        """Add many points as (x, y, name) tuples"""
+        self._prog.add_points([(_coord(x), _coord(y), name) for x, y, name in points])
+
+    def add_cols(self, facts: List[Tuple[str, str, str]]):
+        """Add many collinearity facts as (a, b, c) tuples"""
+        self._prog.add_cols(facts)
+
+    def add_paras(self, facts: List[Tuple[str, str, str, str]]):
+        """Add many parallel facts as (a, b, c, d) tuples"""
+        self._prog.add_paras(facts)
+
+    def add_perps(self, facts: List[Tuple[str, str, str, str]]):
+        """Add many perpendicular facts as (a, b, c, d) tuples"""
+        self._prog.add_perps(facts)
+
+    def add_congs(self, facts: List[Tuple[str, str, str, str]]):
+        """Add many congruence facts as (a, b, c, d) tuples"""
+        self._prog.add_congs(facts)
+
+    def add_eqangles(self, facts: List[Tuple[str, str, str, str, str, str]]):
+        """Add many equal angle facts as (a, b, c, d, e, f) tuples"""
+        self._prog.add_eqangles(facts)
+
+    def add_cyclics(self, facts: List[Tuple[str, str, str, str]]):
+        """Add many cyclic facts as (a, b, c, d) tuples"""
+        self._prog.add_cyclics(facts)
+
+    def add_sameclocks(self, facts: List[Tuple[str, str, str, str, str, str]]):
+        """Add many sameclock facts as (a, b, c, d, e, f) tuples"""
+        self._prog.add_sameclocks(facts)
+
+    def add_midps(self, facts: List[Tuple[str, str, str]]):
+        """Add many midpoint facts as (m, a, b) tuples"""
+        self._prog.add_midps(facts)
+
+    def add_contri1s(self, facts: List[Tuple[str, str, str, str, str, str]]):
+        """Add many congruent triangle facts (same orientation) as (a, b, c, d, e, f) tuples"""
+        self._prog.add_contri1s(facts)
+
+    def add_contri2s(self, facts: List[Tuple[str, str, str, str, str, str]]):
+        """Add many congruent triangle facts (opposite orientation) as (a, b, c, d, e, f) tuples"""
+        self._prog.add_contri2s(facts)
+
+    def add_simtri1s(self, facts: List[Tuple[str, str, str, str, str, str]]):
+        """Add many similar triangle facts (same orientation) as (a, b, c, d, e, f) tuples"""
+        self._prog.add_simtri1s(facts)
+
+    def add_simtri2s(self, facts: List[Tuple[str, str, str, str, str, str]]):
+        """Add many similar triangle facts (opposite orientation) as (a, b, c, d, e, f) tuples"""
+        self._prog.add_simtri2s(facts)
+
+    def add_eqratios(self, facts: List[Tuple[str, str, str, str, str, str, str, str]]):
+        """Add many equal ratio facts as (a, b, c, d, e, f, g, h) tuples"""
+        self._prog.add_eqratios(facts)
+
+    def add_aconsts(self, facts: List[Tuple[str, str, str, int, int]]):
+        """Add many constant angle facts as (a, b, c, m, n) tuples"""
+        self._prog.add_aconsts(facts)
+
+    def add_lconsts(self, facts: List[Tuple[str, str, int, int]]):
+        """Add many constant length facts as (a, b, m, n) tuples"""
+        self._prog.add_lconsts(facts)
+
+    def add_rconsts(self, facts: List[Tuple[str, str, str, str, int, int]]):
+        """Add many constant ratio facts as (a, b, c, d, m, n) tuples"""
+        self._prog.add_rconsts(facts)
+
+    def add_tangents(self, facts: List[Tuple[str, str, str, str]]):
+        """Add many tangency facts as (a, b, o, p) tuples"""
+        self._prog.add_tangents(facts)
+
+    def enable_rule(self, rule: str):
+        """Enable an opt-in deduction rule by name (e.g. "orthocenter_reflection"), or switch a
+        disabled default rule back on"""
+        self._prog.enable_rule(rule)
+
+    def disable_rule(self, rule: str):
+        """Disable a previously enabled opt-in deduction rule, or switch off a default rule that
+        allows it ("para_trans", the transitivity of parallelism; "common_parallel" still
+        combines two input para facts sharing a line)"""
+        self._prog.disable_rule(rule)
+
+    def set_max_derivations_per_fact(self, cap: Optional[int]):
+        """Keep at most `cap` derivations per fact (at least 1), or all of them if None"""
+        self._prog.set_max_derivations_per_fact(cap)
+
+    def clear(self):
+        """Remove all points and facts, keeping the enabled rules and derivation cap"""
+        self._prog.clear()
+
+    def clear_derived(self):
+        """Remove the derived facts, keeping the points and input facts"""
+        self._prog.clear_derived()
+
+    def set_max_iterations(self, max_iterations: Optional[int]):
+        """Stop each run after this many iterations of the main loop, or never if None"""
+        self._prog.set_max_iterations(max_iterations)
+
+    def infer_from_coordinates(self) -> int:
+        """Seed later runs with the col/para/perp/cong/midp facts true for the current points (at most 16); returns their count"""
+        return self._prog.infer_from_coordinates()
+
+    def saturated(self) -> bool:
+        """Whether the last run reached its fixpoint instead of stopping at max_iterations or a timeout"""
+        return self._prog.saturated()
+
+    def validate(self) -> List[str]:
+        """Get the ids of input facts that name a point never added with add_point"""
+        return self._prog.validate()
+
     def run(self):
         """Execute the Datalog deduction rules"""
         self._prog.run()
 
+    def run_with_timeout(self, millis: int) -> bool:
+        """Execute the deduction rules for at most `millis` milliseconds, keeping the facts derived so far; return whether they saturated"""
+        return self._prog.run_with_timeout(millis)
+
+    def run_without_provenance(self):
+        """Execute the deduction rules without recording derivations, for has_* queries only"""
+        self._prog.run_without_provenance()
+
+    def run_incremental(self):
+        """Continue the last run with only the points and facts added since, or run fully. Saves
+        iterations, not time: the first iteration still joins every stored fact."""
+        self._prog.run_incremental()
+
+    def to_bincode(self, include_derived: bool = True) -> bytes:
+        """Serialize points, input facts and (optionally) derived facts to compact bytes"""
+        return self._prog.to_bincode(include_derived)
+
+    @classmethod
+    def from_bincode(cls, data: bytes) -> "DeductiveDatabase":
+        """Load a database previously serialized with to_bincode"""
+        db = cls.__new__(cls)
+        db._prog = _DeductiveDatabase.from_bincode(data)
+        return db
+
+    def to_json(self) -> str:
+        """Serialize points, input facts and derived facts with provenance to JSON"""
+        return self._prog.to_json()
+
+    @classmethod
+    def from_json(cls, s: str) -> "DeductiveDatabase":
+        """Load a database previously serialized with to_json, derived facts included"""
+        db = cls.__new__(cls)
+        db._prog = _DeductiveDatabase.from_json(s)
+        return db
+
     # Output methods - now return full derivation information
     def get_col(self) -> List[Tuple[str, str, str, List[Tuple[str, List[str]]]]]:
         """
@@ -161,6 +332,304 @@ class DeductiveDatabase:
         """Get all deduced constant angle relationships with full derivation provenance"""
         return self._prog.get_aconst()
 
+    def get_lconst(
+        self,
+    ) -> List[Tuple[str, str, int, int, List[Tuple[str, List[str]]]]]:
+        """Get all deduced constant length relationships with full derivation provenance"""
+        return self._prog.get_lconst()
+
+    def get_rconst(
+        self,
+    ) -> List[Tuple[str, str, str, str, int, int, List[Tuple[str, List[str]]]]]:
+        """Get all deduced constant ratio relationships with full derivation provenance"""
+        return self._prog.get_rconst()
+
+    def get_eq(self) -> List[Tuple[str, str, List[Tuple[str, List[str]]]]]:
+        """Get all pairs of point names forced to be the same point, with provenance"""
+        return self._prog.get_eq()
+
+    def get_tangent(
+        self,
+    ) -> List[Tuple[str, str, str, str, List[Tuple[str, List[str]]]]]:
+        """Get all deduced tangent lines with full derivation provenance"""
+        return self._prog.get_tangent()
+
+    def get_all_facts(self) -> List[Tuple[str, List[str], List[Tuple[str, List[str]]]]]:
+        """Get every deduced fact as (predicate, args, derivations), numeric args as strings"""
+        return self._prog.get_all_facts()
+
+    def get_facts(self, predicate: str) -> List[Fact]:
+        """Get all deduced facts of a predicate (e.g. "col") as objects with predicate, args and derivations"""
+        return self._prog.get_facts(predicate)
+
+    def stats(self) -> dict:
+        """
+        Count what the last run derived.
+
+        Returns:
+            {"relations": {predicate: derived tuple count},
+             "rules": {rule_name: recorded derivation count},
+             "iterations": main loop iterations}
+        """
+        return self._prog.stats()
+
+    def coverage(self) -> List[Tuple[str, int, int]]:
+        """
+        Per predicate, compare the numerically true facts over all points with those derived.
+
+        Returns:
+            List of (predicate, true_count, derived_count) tuples
+        """
+        return self._prog.coverage()
+
+    def contradictions(self) -> List[Tuple[str, str]]:
+        """
+        Get pairs of derived fact ids that cannot both hold, e.g. para and perp of the same lines.
+
+        Returns:
+            List of (fact_id, fact_id) tuples; a congruence refuted by the point
+            coordinates is paired with "coordinates"
+        """
+        return self._prog.contradictions()
+
+    def verify(self) -> List[str]:
+        """
+        Get ids of derived col, para, perp, cong and midp facts that are false for the point coordinates.
+
+        Returns:
+            List of fact ids; empty when every checked fact holds numerically
+        """
+        return self._prog.verify()
+
+    def explain_contradiction(
+        self, fact1: Tuple[str, List[str]], fact2: Tuple[str, List[str]]
+    ) -> Tuple[str, str]:
+        """
+        Get the proofs of two conflicting facts, e.g. ("para", ["A", "B", "C", "D"]).
+
+        Returns:
+            (proof_of_fact1, proof_of_fact2), where a fact that was never derived
+            is reported as such instead of a proof
+        """
+        return self._prog.explain_contradiction(fact1, fact2)
+
+    def proof_dag(self, pred: str, args: List[str]) -> List[Tuple[str, str, List[str]]]:
+        """
+        Get the proof of a fact as a DAG with shared subproofs listed once.
+
+        Returns:
+            List of (fact_id, rule_name, parent_fact_ids) nodes, parents first,
+            or an empty list if the fact was not derived
+        """
+        return self._prog.proof_dag(pred, args)
+
+    def to_dot(self) -> str:
+        """
+        Get every derivation as a Graphviz DOT graph.
+
+        Facts are boxes (axioms filled grey); each derivation is a small node labeled
+        with its rule, linked from its premises and to the fact it proves.
+        """
+        return self._prog.to_dot()
+
+    @staticmethod
+    def canonical_fact_id(fact_id: str) -> str:
+        """
+        Get the canonical form of a fact id, e.g. "cong(B,A,D,C)" -> "cong(A,B,C,D)".
+
+        Symmetric argument orders of a fact share one id, which is the form used for
+        provenance parents and returned by the proof methods.
+        """
+        return _DeductiveDatabase.canonical_fact_id(fact_id)
+
+    def proof_tree(self, fact_id: str) -> Optional[dict]:
+        """
+        Get the proof of a fact id such as "para(A,B,C,D)" as a nested tree down to the axioms.
+
+        Returns:
+            {"fact": fact_id, "rule": rule_name, "premises": [subtrees]}, or None if the
+            fact was not derived
+        """
+        return self._prog.proof_tree(fact_id)
+
+    def shortest_proof(self, fact_id: str) -> Optional[List[dict]]:
+        """
+        Get the minimum-depth proof of a fact id such as "para(A,B,C,D)".
+
+        Returns:
+            List of {"fact", "rule", "premises", "depth"} steps, parents first with
+            axioms at depth 0, or None if the fact was not derived
+        """
+        return self._prog.shortest_proof(fact_id)
+
+    # Goal queries - match a fact up to the symmetries of its predicate
+    def has_col(self, a: str, b: str, c: str) -> bool:
+        """Whether collinearity of A, B, C was derived"""
+        return self._prog.has_col(a, b, c)
+
+    def has_para(self, a: str, b: str, c: str, d: str) -> bool:
+        """Whether AB ∥ CD was derived"""
+        return self._prog.has_para(a, b, c, d)
+
+    def has_perp(self, a: str, b: str, c: str, d: str) -> bool:
+        """Whether AB ⟂ CD was derived"""
+        return self._prog.has_perp(a, b, c, d)
+
+    def has_cong(self, a: str, b: str, c: str, d: str) -> bool:
+        """Whether AB = CD was derived"""
+        return self._prog.has_cong(a, b, c, d)
+
+    def has_eqangle(self, a: str, b: str, c: str, d: str, e: str, f: str) -> bool:
+        """Whether ∠ABC = ∠DEF was derived"""
+        return self._prog.has_eqangle(a, b, c, d, e, f)
+
+    def has_cyclic(self, a: str, b: str, c: str, d: str) -> bool:
+        """Whether A, B, C, D were derived to lie on a circle"""
+        return self._prog.has_cyclic(a, b, c, d)
+
+    def has_midp(self, m: str, a: str, b: str) -> bool:
+        """Whether M was derived to be the midpoint of AB"""
+        return self._prog.has_midp(m, a, b)
+
+    def has_contri1(self, a: str, b: str, c: str, d: str, e: str, f: str) -> bool:
+        """Whether triangle ABC ≅ triangle DEF (same orientation) was derived"""
+        return self._prog.has_contri1(a, b, c, d, e, f)
+
+    def has_contri2(self, a: str, b: str, c: str, d: str, e: str, f: str) -> bool:
+        """Whether triangle ABC ≅ triangle DEF (opposite orientation) was derived"""
+        return self._prog.has_contri2(a, b, c, d, e, f)
+
+    def has_simtri1(self, a: str, b: str, c: str, d: str, e: str, f: str) -> bool:
+        """Whether triangle ABC ~ triangle DEF (same orientation) was derived"""
+        return self._prog.has_simtri1(a, b, c, d, e, f)
+
+    def has_simtri2(self, a: str, b: str, c: str, d: str, e: str, f: str) -> bool:
+        """Whether triangle ABC ~ triangle DEF (opposite orientation) was derived"""
+        return self._prog.has_simtri2(a, b, c, d, e, f)
+
+    def has_eqratio(
+        self, a: str, b: str, c: str, d: str, e: str, f: str, g: str, h: str
+    ) -> bool:
+        """Whether (AB/CD) = (EF/GH) was derived"""
+        return self._prog.has_eqratio(a, b, c, d, e, f, g, h)
+
+    def has_aconst(self, a: str, b: str, c: str, m: int, n: int) -> bool:
+        """Whether ∠ABC = mπ/n was derived"""
+        return self._prog.has_aconst(a, b, c, m, n)
+
+    def has_lconst(self, a: str, b: str, m: int, n: int) -> bool:
+        """Whether |AB| = m/n was derived"""
+        return self._prog.has_lconst(a, b, m, n)
+
+    def has_rconst(self, a: str, b: str, c: str, d: str, m: int, n: int) -> bool:
+        """Whether |AB| / |CD| = m/n was derived"""
+        return self._prog.has_rconst(a, b, c, d, m, n)
+
+    def has_eq(self, a: str, b: str) -> bool:
+        """Whether A and B were derived to be the same point"""
+        return self._prog.has_eq(a, b)
+
+    def has_tangent(self, a: str, b: str, o: str, p: str) -> bool:
+        """Whether line AB was derived to be tangent at P to the circle centered O"""
+        return self._prog.has_tangent(a, b, o, p)
+
+    # Goal explanations - derivations of a fact matched up to symmetry, or None if not derived
+    def explain_col(self, a: str, b: str, c: str) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the (rule_name, parent_fact_ids) derivations of collinearity of A, B, C"""
+        return self._prog.explain_col(a, b, c)
+
+    def explain_para(
+        self, a: str, b: str, c: str, d: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of AB ∥ CD"""
+        return self._prog.explain_para(a, b, c, d)
+
+    def explain_perp(
+        self, a: str, b: str, c: str, d: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of AB ⟂ CD"""
+        return self._prog.explain_perp(a, b, c, d)
+
+    def explain_cong(
+        self, a: str, b: str, c: str, d: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of AB = CD"""
+        return self._prog.explain_cong(a, b, c, d)
+
+    def explain_eqangle(
+        self, a: str, b: str, c: str, d: str, e: str, f: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of ∠ABC = ∠DEF"""
+        return self._prog.explain_eqangle(a, b, c, d, e, f)
+
+    def explain_cyclic(
+        self, a: str, b: str, c: str, d: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of A, B, C, D lying on a circle"""
+        return self._prog.explain_cyclic(a, b, c, d)
+
+    def explain_midp(self, m: str, a: str, b: str) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of M being the midpoint of AB"""
+        return self._prog.explain_midp(m, a, b)
+
+    def explain_contri1(
+        self, a: str, b: str, c: str, d: str, e: str, f: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of triangle ABC ≅ triangle DEF (same orientation)"""
+        return self._prog.explain_contri1(a, b, c, d, e, f)
+
+    def explain_contri2(
+        self, a: str, b: str, c: str, d: str, e: str, f: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of triangle ABC ≅ triangle DEF (opposite orientation)"""
+        return self._prog.explain_contri2(a, b, c, d, e, f)
+
+    def explain_simtri1(
+        self, a: str, b: str, c: str, d: str, e: str, f: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of triangle ABC ~ triangle DEF (same orientation)"""
+        return self._prog.explain_simtri1(a, b, c, d, e, f)
+
+    def explain_simtri2(
+        self, a: str, b: str, c: str, d: str, e: str, f: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of triangle ABC ~ triangle DEF (opposite orientation)"""
+        return self._prog.explain_simtri2(a, b, c, d, e, f)
+
+    def explain_eqratio(
+        self, a: str, b: str, c: str, d: str, e: str, f: str, g: str, h: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of (AB/CD) = (EF/GH)"""
+        return self._prog.explain_eqratio(a, b, c, d, e, f, g, h)
+
+    def explain_aconst(
+        self, a: str, b: str, c: str, m: int, n: int
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of ∠ABC = mπ/n"""
+        return self._prog.explain_aconst(a, b, c, m, n)
+
+    def explain_lconst(
+        self, a: str, b: str, m: int, n: int
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of |AB| = m/n"""
+        return self._prog.explain_lconst(a, b, m, n)
+
+    def explain_rconst(
+        self, a: str, b: str, c: str, d: str, m: int, n: int
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of |AB| / |CD| = m/n"""
+        return self._prog.explain_rconst(a, b, c, d, m, n)
+
+    def explain_eq(self, a: str, b: str) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of A and B being the same point"""
+        return self._prog.explain_eq(a, b)
+
+    def explain_tangent(
+        self, a: str, b: str, o: str, p: str
+    ) -> Optional[List[Tuple[str, List[str]]]]:
+        """Get the derivations of line AB being tangent at P to the circle centered O"""
+        return self._prog.explain_tangent(a, b, o, p)
+
     def get_similar_triangles(
         self,
     ) -> List[Tuple[str, str, str, str, str, str, List[Tuple[str, List[str]]]]]:
@@ -1,772 +1,4157 @@
+// The wrappers pyo3 0.22 generates for PyResult methods convert the PyErr with `From`. They
+// sit next to the #[pymethods] impl and don't inherit its attributes, so this can't be narrower.
+#![allow(clippy::useless_conversion)]
+
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyBytes, PyDict, PyList};
 use pyo3::{Bound, types::PyModule};
 use ascent::ascent;
 use ascent::Lattice;
-use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+// Leading byte of `to_bincode` output, bumped whenever the layout changes
+const BINCODE_FORMAT_VERSION: u8 = 1;
+
+// Largest point set `coverage` will enumerate (eqangle is O(n^6))
+const MAX_COVERAGE_POINTS: usize = 16;
+
+// Largest point set `infer_from_coordinates` will read facts from (para, perp and cong
+// compare every two segments, O(n^4))
+const MAX_INFERRED_POINTS: usize = 16;
+
+// Predicates with an exact numeric test, and their arities
+const NUMERIC_PREDICATES: [(&str, usize); 7] = [
+    ("col", 3),
+    ("para", 4),
+    ("perp", 4),
+    ("cong", 4),
+    ("eqangle", 6),
+    ("cyclic", 4),
+    ("midp", 3),
+];
+
+// Argument permutations (new[i] = old[perm[i]]) under which each predicate is symmetric,
+// mirroring the symmetry rules of the program
+const SYMMETRIES: [(&str, &[&[usize]]); 13] = [
+    ("col", &[&[2, 1, 0], &[0, 2, 1]]),
+    ("para", &[&[2, 3, 0, 1], &[1, 0, 2, 3], &[0, 1, 3, 2]]),
+    ("perp", &[&[2, 3, 0, 1], &[1, 0, 2, 3], &[0, 1, 3, 2]]),
+    ("cong", &[&[2, 3, 0, 1], &[1, 0, 2, 3], &[0, 1, 3, 2]]),
+    ("eqangle", &[&[3, 4, 5, 0, 1, 2], &[2, 1, 0, 5, 4, 3]]),
+    ("cyclic", &[&[1, 2, 3, 0], &[0, 2, 1, 3]]),
+    ("midp", &[&[0, 2, 1]]),
+    ("sameclock", &[&[3, 4, 5, 0, 1, 2], &[0, 1, 2, 5, 3, 4], &[2, 1, 0, 5, 4, 3]]),
+    ("eqratio", &[&[1, 0, 2, 3, 4, 5, 6, 7], &[4, 5, 6, 7, 0, 1, 2, 3], &[2, 3, 0, 1, 6, 7, 4, 5], &[0, 1, 4, 5, 2, 3, 6, 7]]),
+    ("lconst", &[&[1, 0, 2, 3]]),
+    ("rconst", &[&[2, 3, 0, 1, 5, 4], &[1, 0, 2, 3, 4, 5], &[0, 1, 3, 2, 4, 5]]),
+    ("eq", &[&[1, 0]]),
+    ("tangent", &[&[1, 0, 2, 3]]),
+];
+
+// A point coordinate: an exact integer, or a floating-point value compared within the
+// database's epsilon. A database holds one kind, chosen when it is constructed.
+#[derive(Clone, Copy, Debug)]
+enum Coord {
+    Int(i64),
+    Float(f64),
+}
+
+impl Coord {
+    fn to_f64(self) -> f64 {
+        match self {
+            Coord::Int(v) => v as f64,
+            Coord::Float(v) => v,
+        }
+    }
+}
+
+// Floats compare by bit pattern, so that points can key the relations of the program
+impl PartialEq for Coord {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Coord::Int(a), Coord::Int(b)) => a == b,
+            (Coord::Float(a), Coord::Float(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Coord {}
+
+impl Hash for Coord {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        match self {
+            Coord::Int(v) => (0u8, *v).hash(state),
+            Coord::Float(v) => (1u8, v.to_bits()).hash(state),
+        }
+    }
+}
+
+// JSON keeps coordinates as plain numbers (an integer or a float literal); bincode, which
+// cannot tell the two apart, stores the variant
+impl Serialize for Coord {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match (*self, s.is_human_readable()) {
+            (Coord::Int(v), true) => s.serialize_i64(v),
+            (Coord::Float(v), true) => s.serialize_f64(v),
+            (Coord::Int(v), false) => s.serialize_newtype_variant("Coord", 0, "Int", &v),
+            (Coord::Float(v), false) => s.serialize_newtype_variant("Coord", 1, "Float", &v),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Coord {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Number {
+            Int(i64),
+            Float(f64),
+        }
+        #[derive(Deserialize)]
+        enum Tagged {
+            Int(i64),
+            Float(f64),
+        }
+        let (int, float) = if d.is_human_readable() {
+            match Number::deserialize(d)? {
+                Number::Int(v) => (Some(v), 0.0),
+                Number::Float(v) => (None, v),
+            }
+        } else {
+            match Tagged::deserialize(d)? {
+                Tagged::Int(v) => (Some(v), 0.0),
+                Tagged::Float(v) => (None, v),
+            }
+        };
+        Ok(int.map_or(Coord::Float(float), Coord::Int))
+    }
+}
+
+impl<'py> FromPyObject<'py> for Coord {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        match ob.extract::<i64>() {
+            Ok(v) => Ok(Coord::Int(v)),
+            Err(_) => Ok(Coord::Float(ob.extract::<f64>()?)),
+        }
+    }
+}
+
+impl IntoPy<PyObject> for Coord {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        match self {
+            Coord::Int(v) => v.into_py(py),
+            Coord::Float(v) => v.into_py(py),
+        }
+    }
+}
+
+// The points as exact integers, or None if any coordinate is floating-point
+fn exact_coords(l: &[(Coord, Coord)]) -> Option<Vec<(i64, i64)>> {
+    l.iter()
+        .map(|&(x, y)| match (x, y) {
+            (Coord::Int(x), Coord::Int(y)) => Some((x, y)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn float_coords(l: &[(Coord, Coord)]) -> Vec<(f64, f64)> {
+    l.iter().map(|&(x, y)| (x.to_f64(), y.to_f64())).collect()
+}
+
+// Whether a floating-point quantity is zero within epsilon
+fn is_negligible(v: f64, eps: f64) -> bool {
+    v.abs() <= eps
+}
+
+// Twice the signed area of the polygon (shoelace formula)
+fn signed_area<T>(l: &[(T, T)]) -> T
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T> + std::iter::Sum,
+{
+    let edge_length = |p: (T, T), q: (T, T)| (q.0 - p.0) * (q.1 + p.1);
+
+    (0..l.len())
+        .map(|i| edge_length(l[i], l[(i + 1) % l.len()]))
+        .sum()
+}
+
+// Sign of the signed area of the polygon, 0 when it is degenerate (within epsilon for
+// floating-point coordinates)
+fn area_sign(l: &[(Coord, Coord)], eps: f64) -> i8 {
+    match exact_coords(l) {
+        Some(p) => signed_area(&p).signum() as i8,
+        None => {
+            let area = signed_area(&float_coords(l));
+            if is_negligible(area, eps) { 0 } else if area > 0.0 { 1 } else { -1 }
+        }
+    }
+}
+
+// Whether two triangles wind the same way, or None if either is degenerate (collinear or with
+// repeated vertices), so each rule decides explicitly whether to fire in limit configurations
+fn same_orientation(l1: Vec<(Coord, Coord)>, l2: Vec<(Coord, Coord)>, eps: f64) -> Option<bool> {
+    let (sign1, sign2) = (area_sign(&l1, eps), area_sign(&l2, eps));
+    if sign1 == 0 || sign2 == 0 {
+        None
+    } else {
+        Some(sign1 == sign2)
+    }
+}
+
+fn is_nondegenerate(l: Vec<(Coord, Coord)>, eps: f64) -> bool {
+    area_sign(&l, eps) != 0
+}
+
+// x, or q if x is the point p
+fn rename_point(x: &str, p: &str, q: &str) -> String {
+    if x == p { q.to_string() } else { x.to_string() }
+}
+
+// Whether the ratios ab/cd and ef/gh are over the same two segments, up to endpoint order
+fn same_ratio(r1: [&String; 4], r2: [&String; 4]) -> bool {
+    let same_segment = |a: &String, b: &String, c: &String, d: &String| (a == c && b == d) || (a == d && b == c);
+    same_segment(r1[0], r1[1], r2[0], r2[1]) && same_segment(r1[2], r1[3], r2[2], r2[3])
+}
+
+// Whether p lies strictly inside segment ab, given that the three points are collinear
+fn is_between(p: (Coord, Coord), a: (Coord, Coord), b: (Coord, Coord), eps: f64) -> bool {
+    match exact_coords(&[p, a, b]).as_deref() {
+        Some(&[p, a, b]) => {
+            let (pax, pay) = (a.0 as i128 - p.0 as i128, a.1 as i128 - p.1 as i128);
+            let (pbx, pby) = (b.0 as i128 - p.0 as i128, b.1 as i128 - p.1 as i128);
+            pax * pbx + pay * pby < 0
+        }
+        _ => {
+            let [p, a, b] = [p, a, b].map(|(x, y)| (x.to_f64(), y.to_f64()));
+            let dot = (a.0 - p.0) * (b.0 - p.0) + (a.1 - p.1) * (b.1 - p.1);
+            dot < 0.0 && !is_negligible(dot, eps)
+        }
+    }
+}
+
+// Whether p and q are different points, more than epsilon apart in squared distance for
+// floating-point coordinates
+fn distinct_positions(p: (Coord, Coord), q: (Coord, Coord), eps: f64) -> bool {
+    match exact_coords(&[p, q]).as_deref() {
+        Some(&[p, q]) => p != q,
+        _ => {
+            let [p, q] = [p, q].map(|(x, y)| (x.to_f64(), y.to_f64()));
+            !is_negligible((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2), eps)
+        }
+    }
+}
+
+// Check of a predicate against point coordinates, or None if the predicate has no numeric
+// test. Integer coordinates are checked exactly, floating-point ones within epsilon. Angles
+// are directed modulo π, matching `eqangle` and `aconst`.
+fn check_numeric(pred: &str, p: &[(Coord, Coord)], eps: f64) -> Option<bool> {
+    match exact_coords(p) {
+        Some(p) => {
+            let p: Vec<(i128, i128)> = p.iter().map(|&(x, y)| (x as i128, y as i128)).collect();
+            check_with(pred, &p, |v| v == 0)
+        }
+        None => check_with(pred, &float_coords(p), |v| is_negligible(v, eps)),
+    }
+}
+
+// `check_numeric` over coordinates of either kind, with `is_zero` deciding which values vanish
+fn check_with<T>(pred: &str, p: &[(T, T)], is_zero: impl Fn(T) -> bool) -> Option<bool>
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>,
+{
+    let sub = |a: (T, T), b: (T, T)| (b.0 - a.0, b.1 - a.1);
+    let cross = |u: (T, T), v: (T, T)| u.0 * v.1 - u.1 * v.0;
+    let dot = |u: (T, T), v: (T, T)| u.0 * v.0 + u.1 * v.1;
+
+    match pred {
+        "col" => Some(is_zero(cross(sub(p[0], p[1]), sub(p[0], p[2])))),
+        "para" => Some(is_zero(cross(sub(p[0], p[1]), sub(p[2], p[3])))),
+        "perp" => Some(is_zero(dot(sub(p[0], p[1]), sub(p[2], p[3])))),
+        "cong" => {
+            let (u, v) = (sub(p[0], p[1]), sub(p[2], p[3]));
+            Some(is_zero(dot(u, u) - dot(v, v)))
+        }
+        "eqangle" => {
+            let (u1, v1) = (sub(p[0], p[1]), sub(p[1], p[2]));
+            let (u2, v2) = (sub(p[3], p[4]), sub(p[4], p[5]));
+            Some(is_zero(cross(u1, v1) * dot(u2, v2) - cross(u2, v2) * dot(u1, v1)))
+        }
+        "cyclic" => {
+            let rows: Vec<(T, T, T)> = p[1..4].iter()
+                .map(|&q| {
+                    let (x, y) = sub(p[0], q);
+                    (x, y, x * x + y * y)
+                })
+                .collect();
+            let det = rows[0].0 * (rows[1].1 * rows[2].2 - rows[1].2 * rows[2].1)
+                - rows[0].1 * (rows[1].0 * rows[2].2 - rows[1].2 * rows[2].0)
+                + rows[0].2 * (rows[1].0 * rows[2].1 - rows[1].1 * rows[2].0);
+            Some(!is_zero(cross(sub(p[0], p[1]), sub(p[0], p[2]))) && is_zero(det))
+        }
+        "midp" => {
+            let (u, v) = (sub(p[1], p[0]), sub(p[0], p[2]));
+            Some(is_zero(u.0 - v.0) && is_zero(u.1 - v.1))
+        }
+        _ => None,
+    }
+}
+
+// Every argument order of a fact reachable through the symmetries of its predicate,
+// starting with the given order
+fn symmetric_variants<T: Clone + PartialEq>(pred: &str, args: Vec<T>) -> Vec<Vec<T>> {
+    let perms: Vec<&[usize]> = SYMMETRIES.iter()
+        .filter(|(p, _)| *p == pred)
+        .flat_map(|(_, perms)| perms.iter().copied())
+        .filter(|perm| perm.len() == args.len())
+        .collect();
+
+    let mut variants = vec![args];
+    let mut i = 0;
+    while i < variants.len() {
+        for perm in &perms {
+            let next: Vec<T> = perm.iter().map(|&j| variants[i][j].clone()).collect();
+            if !variants.contains(&next) {
+                variants.push(next);
+            }
+        }
+        i += 1;
+    }
+    variants
+}
+
+// A predicate and the argument permutations that leave its facts unchanged
+type SymmetryGroup = (&'static str, Vec<Vec<usize>>);
+
+// Every argument permutation of each predicate in SYMMETRIES, closed under composition
+fn symmetry_groups() -> &'static [SymmetryGroup] {
+    static GROUPS: OnceLock<Vec<SymmetryGroup>> = OnceLock::new();
+    GROUPS.get_or_init(|| {
+        SYMMETRIES.iter()
+            .map(|(pred, perms)| (*pred, symmetric_variants(pred, (0..perms[0].len()).collect())))
+            .collect()
+    })
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+// Reduce the angle mπ/n modulo π to lowest terms with 0 <= m < n
+fn normalize_angle(m: i32, n: i32) -> (i32, i32) {
+    reduce_angle(m as i64, n as i64)
+}
+
+fn reduce_angle(m: i64, n: i64) -> (i32, i32) {
+    let (m, n) = if n < 0 { (-m, -n) } else { (m, n) };
+    let m = m.rem_euclid(n);
+    let g = gcd(m, n);
+    ((m / g) as i32, (n / g) as i32)
+}
+
+// Sum of the angles m1π/n1 and m2π/n2, normalized modulo π
+fn add_angles(m1: i32, n1: i32, m2: i32, n2: i32) -> (i32, i32) {
+    reduce_angle(
+        m1 as i64 * n2 as i64 + m2 as i64 * n1 as i64,
+        n1 as i64 * n2 as i64,
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+struct Derivation {
+    rule: String,
+    parents: BTreeSet<String>,
+}
+
+impl Derivation {
+    fn axiom() -> Self {
+        Derivation {
+            rule: "axiom".to_string(),
+            parents: BTreeSet::new(),
+        }
+    }
+
+    fn new(rule: &str, parents: Vec<String>) -> Self {
+        Derivation {
+            rule: rule.to_string(),
+            parents: parents.into_iter().collect(),
+        }
+    }
+}
+
+// Settings of a run that shape every provenance it builds, passed to the rules as the only
+// row of `provenance_settings`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ProvenanceSettings {
+    // Whether derivations are recorded at all; off for `run_without_provenance`
+    track: bool,
+    // Most derivations kept per fact, unbounded if None
+    max_derivations: Option<usize>,
+}
+
+impl ProvenanceSettings {
+    fn axiom(&self) -> Provenance {
+        self.provenance(Derivation::axiom)
+    }
+
+    // The parent ids are only built when derivations are tracked
+    fn derive(&self, rule: &str, parents: impl FnOnce() -> Vec<String>) -> Provenance {
+        self.provenance(|| Derivation::new(rule, parents()))
+    }
+
+    // Without provenance tracking every fact has the empty provenance, so a fact is never
+    // changed once derived and the lattices behave as plain relations
+    fn provenance(&self, derivation: impl FnOnce() -> Derivation) -> Provenance {
+        let mut derivations = BTreeSet::new();
+        if self.track {
+            derivations.insert(derivation());
+        }
+        Provenance { derivations, max_derivations: self.max_derivations }
+    }
+}
+
+// Provenance lattice to track all ways a fact was derived
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+struct Provenance {
+    derivations: BTreeSet<Derivation>,
+    // Cap of the run that built it, applied when more derivations are merged in
+    #[serde(skip)]
+    max_derivations: Option<usize>,
+}
+
+impl Provenance {
+    // Derivations as (rule, parent_fact_ids) pairs, as returned to Python
+    fn to_list(&self) -> Vec<(String, Vec<String>)> {
+        self.derivations.iter()
+            .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
+            .collect()
+    }
+}
+
+impl Lattice for Provenance {
+    fn meet(mut self, other: Self) -> Self {
+        self.meet_mut(other);
+        self
+    }
+
+    // Once a fact holds the maximum number of derivations, a new one only takes the place of
+    // one citing more premises, so axioms and short steps are kept over long chains. Every
+    // replacement lowers the premise count, so a full fact stops changing.
+    fn meet_mut(&mut self, other: Self) -> bool {
+        let mut changed = false;
+        for derivation in other.derivations {
+            if self.derivations.contains(&derivation) {
+                continue;
+            }
+            if self.max_derivations.is_some_and(|cap| self.derivations.len() >= cap) {
+                let widest = self.derivations.iter()
+                    .max_by_key(|d| d.parents.len())
+                    .filter(|d| d.parents.len() > derivation.parents.len())
+                    .cloned();
+                let Some(widest) = widest else { continue };
+                self.derivations.remove(&widest);
+            }
+            self.derivations.insert(derivation);
+            changed = true;
+        }
+        changed
+    }
+
+    fn join_mut(&mut self, other: Self) -> bool {
+        self.meet_mut(other)
+    }
+}
+
+// Id of a fact in its canonical form: the lexicographically smallest argument order among its
+// symmetric variants, so every stored order of a fact (and every provenance parent naming it)
+// gets the same id. Per predicate this is
+//   col, cyclic        all points sorted
+//   midp               endpoints sorted
+//   para, perp, cong   each pair sorted, then the two pairs sorted
+//   eqangle            smallest of abc/def, def/abc, cba/fed, fed/cba
+//   eqratio            the smallest order of ab:cd = ef:gh under pair reversal, swapping the
+//                      two ratios, inverting both ratios and swapping the means
+//   lconst             endpoints sorted
+//   rconst             pairs sorted, then the smaller of ab:cd = m/n and cd:ab = n/m
+//   sameclock          smallest order under swapping the triangles, rotating the second and
+//                      reversing both
+//   tangent            points of the tangent line sorted
+// Numeric args compare as strings; predicates without symmetries keep their order.
+fn fact_id(pred_type: &str, args: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    let args_str: Vec<String> = args.into_iter()
+        .map(|s| s.as_ref().to_string())
+        .collect();
+    let group = symmetry_groups().iter()
+        .find(|(p, perms)| *p == pred_type && perms[0].len() == args_str.len());
+    let Some((_, perms)) = group else {
+        return format!("{}({})", pred_type, args_str.join(","));
+    };
+
+    let canonical = perms.iter()
+        .min_by(|p, q| p.iter().map(|&i| &args_str[i]).cmp(q.iter().map(|&i| &args_str[i])))
+        .map(|perm| perm.iter().map(|&i| args_str[i].as_str()).collect::<Vec<_>>().join(","))
+        .unwrap_or_default();
+    format!("{}({})", pred_type, canonical)
+}
+
+// Canonical form of a fact id given as text, e.g. `cong(B,A,C,D)` -> `cong(A,B,C,D)`;
+// text that is not of the form pred(args) is returned unchanged
+fn canonical_fact_id(id: &str) -> String {
+    let parsed = id.strip_suffix(')')
+        .and_then(|rest| rest.split_once('('));
+    match parsed {
+        Some((pred, args)) if !args.is_empty() => fact_id(pred, args.split(',').map(str::trim)),
+        _ => id.to_string(),
+    }
+}
+
+// Facts in a deterministic order, by arguments and then derivations, independent of the
+// order ascent stored them in
+fn sorted<T: Ord>(mut facts: Vec<T>) -> Vec<T> {
+    facts.sort();
+    facts
+}
+
+// Append an input fact with canonical id `id` unless it was already added, in any of its
+// symmetric orders; `ids` holds the ids of the input facts so far
+fn push_unique<T>(facts: &mut Vec<T>, ids: &mut HashSet<String>, id: String, fact: T) {
+    if ids.insert(id) {
+        facts.push(fact);
+    }
+}
+
+// Append input facts in order, skipping any already added, with `id` giving their ids
+fn extend_unique<T>(facts: &mut Vec<T>, ids: &mut HashSet<String>, new_facts: Vec<T>, id: impl Fn(&T) -> String) {
+    for fact in new_facts {
+        push_unique(facts, ids, id(&fact), fact);
+    }
+}
+
+// Point equality substitution rules spliced into the program by `include_source!`, which
+// calls this macro with the ascent macro and the program tokens before and after it. Each
+// listed clause names p at one argument position per orbit of the relation's symmetries,
+// the symmetry rules reaching the others, and gives a rule deriving the fact with every
+// occurrence of p renamed to q. Facts already naming q are left alone, as renaming would
+// only restate them with a repeated point. Arguments after `;` are numeric.
+macro_rules! eq_subst_rules {
+    ({$($ascent:tt)*}, {$($before:tt)*}, {$($after:tt)*}) => {
+        eq_subst_rules! {
+            @rules {$($ascent)*}, {$($before)*}, {$($after)*}, p, q,
+            col(p, b, c);
+            para(p, b, c, d);
+            perp(p, b, c, d);
+            cong(p, b, c, d);
+            eqangle(p, b, c, d, e, f);
+            eqangle(a, p, c, d, e, f);
+            cyclic(p, b, c, d);
+            sameclock(p, b, c, d, e, f);
+            midp(p, b, c);
+            midp(a, p, c);
+            midp(a, b, p);
+            contri1(p, b, c, d, e, f);
+            contri1(a, p, c, d, e, f);
+            contri1(a, b, p, d, e, f);
+            contri1(a, b, c, p, e, f);
+            contri1(a, b, c, d, p, f);
+            contri1(a, b, c, d, e, p);
+            contri2(p, b, c, d, e, f);
+            contri2(a, p, c, d, e, f);
+            contri2(a, b, p, d, e, f);
+            contri2(a, b, c, p, e, f);
+            contri2(a, b, c, d, p, f);
+            contri2(a, b, c, d, e, p);
+            simtri1(p, b, c, d, e, f);
+            simtri1(a, p, c, d, e, f);
+            simtri1(a, b, p, d, e, f);
+            simtri1(a, b, c, p, e, f);
+            simtri1(a, b, c, d, p, f);
+            simtri1(a, b, c, d, e, p);
+            simtri2(p, b, c, d, e, f);
+            simtri2(a, p, c, d, e, f);
+            simtri2(a, b, p, d, e, f);
+            simtri2(a, b, c, p, e, f);
+            simtri2(a, b, c, d, p, f);
+            simtri2(a, b, c, d, e, p);
+            eqratio(p, b, c, d, e, f, g, h);
+            aconst(p, b, c; m, n);
+            aconst(a, p, c; m, n);
+            aconst(a, b, p; m, n);
+            lconst(p, b; m, n);
+            rconst(p, b, c, d; m, n);
+            tangent(p, b, o, t);
+            tangent(a, b, p, t);
+            tangent(a, b, o, p);
+        }
+    };
+    // p and q come from the first arm, like the listed clauses, so that both name the same
+    // variables
+    (@rules {$($ascent:tt)*}, {$($before:tt)*}, {$($after:tt)*}, $p:ident, $q:ident,
+     $($rel:ident($($x:ident),+ $(; $($k:ident),+)?);)*) => {
+        $($ascent)*! {
+            $($before)*
+            $(
+                $rel($(rename_point($x, $p, $q),)+ $($($k,)+)? settings.derive("eq_subst", || vec![
+                    fact_id(stringify!($rel), [$($x.clone(),)+ $($($k.to_string(),)+)?]),
+                    fact_id("eq", [$p, $q])
+                ])) <--
+                    rule_enabled("eq_substitution".to_string()),
+                    $rel($($x,)+ $($($k,)+)? ?_prov1),
+                    eq($p, $q, ?_prov2),
+                    if [$($x),+].iter().all(|v| *v != $q),
+                    provenance_settings(settings);
+            )*
+            $($after)*
+        }
+    };
+}
+
+// The deduction rules. ascent clones every field of a new row, including the i32 constants
+// of aconst/lconst/rconst, stores each relation as a Vec of its row tuples and expands a
+// negated clause to a unit expression, so clone_on_copy, type_complexity and unused_unit are
+// allowed for the generated code in this module only.
+mod program {
+    #![allow(clippy::clone_on_copy, clippy::type_complexity, clippy::unused_unit)]
+
+    use super::*;
+
+    ascent! {
+        #![generate_run_timeout]
+        pub struct AscentProgram;
+
+        relation point(Coord, Coord, String);
+        // The database's epsilon as its only row, passed to every coordinate check
+        relation epsilon(Coord);
+        // The run's provenance settings as its only row, passed to every rule that derives a fact
+        relation provenance_settings(ProvenanceSettings);
+        relation rule_enabled(String);
+        // Default rules switched off with `disable_rule`
+        relation rule_disabled(String);
+        // The input para facts in every symmetric orientation, for the single step of
+        // `common_parallel`
+        relation input_para(String, String, String, String);
+        // (u, v, x, x2): x2 is the reflection of x over line uv, kept free of provenance so
+        // that rules joining several reflections only rerun when a new pair appears
+        relation line_reflection(String, String, String, String);
+
+        lattice col(String, String, String, Provenance);
+        lattice para(String, String, String, String, Provenance);
+        lattice perp(String, String, String, String, Provenance);
+        lattice cong(String, String, String, String, Provenance);
+        lattice eqangle(String, String, String, String, String, String, Provenance);
+        lattice cyclic(String, String, String, String, Provenance);
+        lattice sameclock(String, String, String, String, String, String, Provenance);
+        lattice eqratio(String, String, String, String, String, String, String, String, Provenance);
+        lattice midp(String, String, String, Provenance);
+        lattice contri1(String, String, String, String, String, String, Provenance);
+        lattice contri2(String, String, String, String, String, String, Provenance);
+        lattice simtri1(String, String, String, String, String, String, Provenance);
+        lattice simtri2(String, String, String, String, String, String, Provenance);
+        lattice aconst(String, String, String, i32, i32, Provenance);
+        lattice lconst(String, String, i32, i32, Provenance);
+        lattice rconst(String, String, String, String, i32, i32, Provenance);
+        // Two point names forced to denote the same point
+        lattice eq(String, String, Provenance);
+        // (l1, l2, o, p): line l1l2 is tangent at p to the circle centered o
+        lattice tangent(String, String, String, String, Provenance);
+
+        // ----------------------------------------------------------------
+        // Relation Properties (Symmetries)
+        // ----------------------------------------------------------------
+
+        col(c, b, a, settings.derive("sym", || vec![fact_id("col", [a, b, c])]))
+            <-- col(a, b, c, ?_prov), provenance_settings(settings);
+        col(a, c, b, settings.derive("sym", || vec![fact_id("col", [a, b, c])]))
+            <-- col(a, b, c, ?_prov), provenance_settings(settings);
+
+        para(c, d, a, b, settings.derive("sym", || vec![fact_id("para", [a, b, c, d])]))
+            <-- para(a, b, c, d, ?_prov), provenance_settings(settings);
+        para(b, a, c, d, settings.derive("sym", || vec![fact_id("para", [a, b, c, d])]))
+            <-- para(a, b, c, d, ?_prov), provenance_settings(settings);
+        para(a, b, d, c, settings.derive("sym", || vec![fact_id("para", [a, b, c, d])]))
+            <-- para(a, b, c, d, ?_prov), provenance_settings(settings);
+
+        perp(c, d, a, b, settings.derive("sym", || vec![fact_id("perp", [a, b, c, d])]))
+            <-- perp(a, b, c, d, ?_prov), provenance_settings(settings);
+        perp(b, a, c, d, settings.derive("sym", || vec![fact_id("perp", [a, b, c, d])]))
+            <-- perp(a, b, c, d, ?_prov), provenance_settings(settings);
+        perp(a, b, d, c, settings.derive("sym", || vec![fact_id("perp", [a, b, c, d])]))
+            <-- perp(a, b, c, d, ?_prov), provenance_settings(settings);
+
+        cong(c, d, a, b, settings.derive("sym", || vec![fact_id("cong", [a, b, c, d])]))
+            <-- cong(a, b, c, d, ?_prov), provenance_settings(settings);
+        cong(b, a, c, d, settings.derive("sym", || vec![fact_id("cong", [a, b, c, d])]))
+            <-- cong(a, b, c, d, ?_prov), provenance_settings(settings);
+        cong(a, b, d, c, settings.derive("sym", || vec![fact_id("cong", [a, b, c, d])]))
+            <-- cong(a, b, c, d, ?_prov), provenance_settings(settings);
+
+        eqangle(d, e, f, a, b, c, settings.derive("sym", || vec![fact_id("eqangle", [a, b, c, d, e, f])]))
+            <-- eqangle(a, b, c, d, e, f, ?_prov), provenance_settings(settings);
+        eqangle(c, b, a, f, e, d, settings.derive("sym", || vec![fact_id("eqangle", [a, b, c, d, e, f])]))
+            <-- eqangle(a, b, c, d, e, f, ?_prov), provenance_settings(settings);
+
+        cyclic(b, c, d, a, settings.derive("sym", || vec![fact_id("cyclic", [a, b, c, d])]))
+            <-- cyclic(a, b, c, d, ?_prov), provenance_settings(settings);
+        cyclic(a, c, b, d, settings.derive("sym", || vec![fact_id("cyclic", [a, b, c, d])]))
+            <-- cyclic(a, b, c, d, ?_prov), provenance_settings(settings);
+
+        midp(m, b, a, settings.derive("sym", || vec![fact_id("midp", [m, a, b])]))
+            <-- midp(m, a, b, ?_prov), provenance_settings(settings);
+
+        sameclock(d, e, f, a, b, c, settings.derive("sym", || vec![fact_id("sameclock", [a, b, c, d, e, f])]))
+            <-- sameclock(a, b, c, d, e, f, ?_prov), provenance_settings(settings);
+        sameclock(a, b, c, f, d, e, settings.derive("sym", || vec![fact_id("sameclock", [a, b, c, d, e, f])]))
+            <-- sameclock(a, b, c, d, e, f, ?_prov), provenance_settings(settings);
+        sameclock(c, b, a, f, e, d, settings.derive("sym", || vec![fact_id("sameclock", [a, b, c, d, e, f])]))
+            <-- sameclock(a, b, c, d, e, f, ?_prov), provenance_settings(settings);
+
+        eqratio(b, a, c, d, e, f, g, h, settings.derive("sym", || vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
+            <-- eqratio(a, b, c, d, e, f, g, h, ?_prov), provenance_settings(settings);
+        eqratio(e, f, g, h, a, b, c, d, settings.derive("sym", || vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
+            <-- eqratio(a, b, c, d, e, f, g, h, ?_prov), provenance_settings(settings);
+        eqratio(c, d, a, b, g, h, e, f, settings.derive("sym", || vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
+            <-- eqratio(a, b, c, d, e, f, g, h, ?_prov), provenance_settings(settings);
+        eqratio(a, b, e, f, c, d, g, h, settings.derive("sym", || vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
+            <-- eqratio(a, b, c, d, e, f, g, h, ?_prov), provenance_settings(settings);
+
+        lconst(b, a, m, n, settings.derive("sym", || vec![fact_id("lconst", [a.clone(), b.clone(), m.to_string(), n.to_string()])]))
+            <-- lconst(a, b, m, n, ?_prov), provenance_settings(settings);
+
+        rconst(c, d, a, b, n, m, settings.derive("sym", || vec![fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()])]))
+            <-- rconst(a, b, c, d, m, n, ?_prov), if *m != 0, provenance_settings(settings);
+        rconst(b, a, c, d, m, n, settings.derive("sym", || vec![fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()])]))
+            <-- rconst(a, b, c, d, m, n, ?_prov), provenance_settings(settings);
+        rconst(a, b, d, c, m, n, settings.derive("sym", || vec![fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()])]))
+            <-- rconst(a, b, c, d, m, n, ?_prov), provenance_settings(settings);
+
+        eq(b, a, settings.derive("sym", || vec![fact_id("eq", [a, b])]))
+            <-- eq(a, b, ?_prov), provenance_settings(settings);
+
+        tangent(b, a, o, p, settings.derive("sym", || vec![fact_id("tangent", [a, b, o, p])]))
+            <-- tangent(a, b, o, p, ?_prov), provenance_settings(settings);
+
+        // ----------------------------------------------------------------
+        // Deductive Rules
+        // ----------------------------------------------------------------
+
+        // Right Angle Equal
+        eqangle(c, b, a, b, e, a, settings.derive("right_angle_eq", || vec![
+            fact_id("perp", [a, b, b_prime, c]),
+            fact_id("perp", [a, e, e_prime, b])
+        ])) <--
+            perp(a, b, b_prime, c, ?_prov1),
+            perp(a, e, e_prime, b, ?_prov2),
+            if b == b_prime && e == e_prime &&
+               a != b && a != c && a != e &&
+               b != c && b != e &&
+               c != e,
+            provenance_settings(settings);
+
+        // Self-Equal Angle is Right
+        // ∠abc = ∠cba as directed angles means 2∠abc = 0 mod π, so ∠abc is 0 or π/2; with
+        // a, b, c not collinear it is not 0, so ba ⟂ bc
+        perp(b, a, b, c, settings.derive("eqangle_perp", || vec![
+            fact_id("eqangle", [a, b, c, c, b, a])
+        ])) <--
+            eqangle(a, b, c, c_prime, b_prime, a_prime, ?_prov),
+            if a == a_prime && b == b_prime && c == c_prime &&
+               a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // AA Similarity
+        simtri1(a, b, c, d, e, f, settings.derive("aa_sim", || vec![
+            fact_id("eqangle", [b, a, c, e, d, f]),
+            fact_id("eqangle", [b, c, a, e, f, d])
+        ])) <--
+            eqangle(b, a, c, e, d, f, ?_prov1),
+            eqangle(b, c, a, e, f, d, ?_prov2),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        simtri2(a, b, c, d, e, f, settings.derive("aa_sim", || vec![
+            fact_id("eqangle", [b, a, c, f, d, e]),
+            fact_id("eqangle", [b, c, a, d, f, e])
+        ])) <--
+            eqangle(b, a, c, f, d, e, ?_prov1),
+            eqangle(b, c, a, d, f, e, ?_prov2),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // SAS Similarity
+        simtri1(a, b, c, d, e, f, settings.derive("sas_sim", || vec![
+            fact_id("eqratio", [a, b, d, e, a, c, d, f]),
+            fact_id("eqangle", [b, a, c, e, d, f])
+        ])) <--
+            eqratio(a, b, d, e, a_prime, c, d_prime, f, ?_prov1),
+            eqangle(b, a, c, e, d, f, ?_prov2),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if a == a_prime && d == d_prime && same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        simtri2(a, b, c, d, e, f, settings.derive("sas_sim", || vec![
+            fact_id("eqratio", [a, b, d, e, a, c, d, f]),
+            fact_id("eqangle", [b, a, c, f, d, e])
+        ])) <--
+            eqratio(a, b, d, e, a_prime, c, d_prime, f, ?_prov1),
+            eqangle(b, a, c, f, d, e, ?_prov2),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if a == a_prime && d == d_prime && same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // SSS Similarity
+        simtri1(a, b, c, d, e, f, settings.derive("sss_sim", || vec![
+            fact_id("eqratio", [a, b, d, e, b, c, e, f]),
+            fact_id("eqratio", [b, c, e, f, a, c, d, f])
+        ])) <--
+            eqratio(a, b, d, e, b_prime, c, e_prime, f, ?_prov1),
+            eqratio(b, c, e, f, a, c_prime, d, f_prime, ?_prov2),
+            if b == b_prime && c == c_prime && e == e_prime && f == f_prime,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        simtri2(a, b, c, d, e, f, settings.derive("sss_sim", || vec![
+            fact_id("eqratio", [a, b, d, e, b, c, e, f]),
+            fact_id("eqratio", [b, c, e, f, a, c, d, f])
+        ])) <--
+            eqratio(a, b, d, e, b_prime, c, e_prime, f, ?_prov1),
+            eqratio(b, c, e, f, a, c_prime, d, f_prime, ?_prov2),
+            if b == b_prime && c == c_prime && e == e_prime && f == f_prime,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // ASA Congruence
+        contri1(a, b, c, d, e, f, settings.derive("asa_cong", || vec![
+            fact_id("eqangle", [b, a, c, e, d, f]),
+            fact_id("eqangle", [c, b, a, f, e, d]),
+            fact_id("cong", [a, b, d, e])
+        ])) <--
+            eqangle(b, a, c, e, d, f, ?_prov1),
+            eqangle(c, b, a, f, e, d, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        contri2(a, b, c, d, e, f, settings.derive("asa_cong", || vec![
+            fact_id("eqangle", [b, a, c, f, d, e]),
+            fact_id("eqangle", [c, b, a, d, e, f]),
+            fact_id("cong", [a, b, d, e])
+        ])) <--
+            eqangle(b, a, c, f, d, e, ?_prov1),
+            eqangle(c, b, a, d, e, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // SAS Congruence
+        contri1(a, b, c, d, e, f, settings.derive("sas_cong", || vec![
+            fact_id("eqangle", [b, a, c, e, d, f]),
+            fact_id("cong", [a, c, d, f]),
+            fact_id("cong", [a, b, d, e])
+        ])) <--
+            eqangle(b, a, c, e, d, f, ?_prov1),
+            cong(a, c, d, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        contri2(a, b, c, d, e, f, settings.derive("sas_cong", || vec![
+            fact_id("eqangle", [b, a, c, f, d, e]),
+            fact_id("cong", [a, c, d, f]),
+            fact_id("cong", [a, b, d, e])
+        ])) <--
+            eqangle(b, a, c, f, d, e, ?_prov1),
+            cong(a, c, d, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // SSS Congruence
+        contri1(a, b, c, d, e, f, settings.derive("sss_cong", || vec![
+            fact_id("cong", [a, c, d, f]),
+            fact_id("cong", [a, b, d, e]),
+            fact_id("cong", [c, b, f, e])
+        ])) <--
+            cong(a, c, d, f, ?_prov1),
+            cong(a, b, d, e, ?_prov2),
+            cong(c, b, f, e, ?_prov3),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        contri2(a, b, c, d, e, f, settings.derive("sss_cong", || vec![
+            fact_id("cong", [a, c, d, f]),
+            fact_id("cong", [a, b, d, e]),
+            fact_id("cong", [c, b, f, e])
+        ])) <--
+            cong(a, c, d, f, ?_prov1),
+            cong(a, b, d, e, ?_prov2),
+            cong(c, b, f, e, ?_prov3),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // Right SSA Congruence
+        contri1(a, b, c, d, e, f, settings.derive("ssa_right_cong", || vec![
+            fact_id("perp", [a, b, a_prime, c]),
+            fact_id("perp", [d, e, d_prime, f]),
+            fact_id("cong", [a, b, d, e]),
+            fact_id("cong", [b, c, e, f])
+        ])) <--
+            perp(a, b, a_prime, c, ?_prov1),
+            perp(d, e, d_prime, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            cong(b, c, e, f, ?_prov4),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+        ) == Some(true) && a == a_prime && d == d_prime,
+            provenance_settings(settings);
+
+        contri2(a, b, c, d, e, f, settings.derive("ssa_right_cong", || vec![
+            fact_id("perp", [a, b, a_prime, c]),
+            fact_id("perp", [d, e, d_prime, f]),
+            fact_id("cong", [a, b, d, e]),
+            fact_id("cong", [b, c, e, f])
+        ])) <--
+            perp(a, b, a_prime, c, ?_prov1),
+            perp(d, e, d_prime, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            cong(b, c, e, f, ?_prov4),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true) && a == a_prime && d == d_prime,
+            provenance_settings(settings);
+
+        // Right SAS Congruence
+        contri1(a, b, c, d, e, f, settings.derive("sas_right_cong", || vec![
+            fact_id("perp", [b, a, a, c]),
+            fact_id("perp", [e, d, d, f]),
+            fact_id("cong", [a, b, d, e]),
+            fact_id("cong", [a, c, d, f])
+        ])) <--
+            perp(b, a, a_prime, c, ?_prov1),
+            perp(e, d, d_prime, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            cong(a, c, d, f, ?_prov4),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)],
+                eps.to_f64()
+            ) == Some(true) && a == a_prime && d == d_prime,
+            provenance_settings(settings);
+
+        contri2(a, b, c, d, e, f, settings.derive("sas_right_cong", || vec![
+            fact_id("perp", [b, a, a, c]),
+            fact_id("perp", [e, d, d, f]),
+            fact_id("cong", [a, b, d, e]),
+            fact_id("cong", [a, c, d, f])
+        ])) <--
+            perp(b, a, a_prime, c, ?_prov1),
+            perp(e, d, d_prime, f, ?_prov2),
+            cong(a, b, d, e, ?_prov3),
+            cong(a, c, d, f, ?_prov4),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)],
+                eps.to_f64()
+            ) == Some(true) && a == a_prime && d == d_prime,
+            provenance_settings(settings);
+
+        // Inscribed Angle Theorem
+        eqangle(a, b, c, c, b, d, settings.derive("inscribed_angle_thm", || vec![
+            fact_id("cong", [o, a, o_prime, b]),
+            fact_id("cong", [o, c, o_prime, b]),
+            fact_id("cong", [o, c, o_prime, a]),
+            fact_id("perp", [o, b, b_prime, d]),
+            fact_id("eqangle", [a, o, c, c_prime, o, b])
+        ])) <--
+            cong(o, a, o_prime, b, ?_prov1),
+            cong(o, c, o_prime, b, ?_prov2),
+            cong(o, c, o_prime, a, ?_prov3),
+            perp(o, b, b_prime, d, ?_prov4),
+            eqangle(a, o, c, c_prime, o, b, ?_prov5),
+            if o == o_prime && b == b_prime && c == c_prime &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            provenance_settings(settings);
+
+        // Converse of Thales's Theorem
+        // o is the midpoint of bd (collinear with it and equidistant from b and d), so the right
+        // angle at r puts r on the circle with diameter bd, as is every y with oy = ob
+        cyclic(b, r, y, d, settings.derive("thales_converse", || vec![
+            fact_id("perp", [b, r, r, d]),
+            fact_id("col", [b, o, d]),
+            fact_id("cong", [b, o, d, o]),
+            fact_id("cong", [y, o, b, o])
+        ])) <--
+            perp(b, r, r_prime, d, ?_prov1),
+            col(b, o, d, ?_prov2),
+            cong(b, o, d, o_prime, ?_prov3),
+            cong(y, o_prime2, b, o_prime3, ?_prov4),
+            if r == r_prime && o == o_prime && o == o_prime2 && o == o_prime3 &&
+               b != r && b != y && b != d && b != o &&
+               r != y && r != d && r != o &&
+               y != d && y != o &&
+               d != o,
+            provenance_settings(settings);
+
+        // Angle on a Diameter (Thales's Theorem)
+        // The midpoint o of ab is as far from p as from a and b, so it is the center of the
+        // circle through a, p, b, q; ab is its diameter and subtends right angles at p and q
+        perp(p, a, p, b, prov.clone()),
+        perp(q, a, q, b, prov) <--
+            cyclic(a, p, b, q, ?_prov1),
+            midp(o, a, b, ?_prov2),
+            cong(o, a, o_prime, p, ?_prov3),
+            if o == o_prime &&
+               a != b && a != p && a != q &&
+               b != p && b != q &&
+               p != q,
+            provenance_settings(settings),
+            let prov = settings.derive("diameter_right_angle", || vec![
+                fact_id("cyclic", [a, p, b, q]),
+                fact_id("midp", [o, a, b]),
+                fact_id("cong", [o, a, o, p])
+            ]);
+
+        // Eqratio is Additive
+        eqratio(a, m, b, n, m, c, n, d, settings.derive("eqratio_additive", || vec![
+            fact_id("eqratio", [a, c, m, c, b, d, n, d]),
+            fact_id("col", [a, m, c]),
+            fact_id("col", [b, n, d])
+        ])) <--
+            eqratio(a, c, m, c_prime, b, d, n, d_prime, ?_prov1),
+            col(a, m, c, ?_prov2),
+            col(b, n, d, ?_prov3),
+            if a != m && a != c &&
+               m != c &&
+               b != n && b != d &&
+               n != d &&
+               c == c_prime && d == d_prime,
+            provenance_settings(settings);
+
+        eqratio(a, c, m, c, b, d, n, d, settings.derive("eqratio_additive", || vec![
+            fact_id("eqratio", [a, m, b, n, m, c, n, d]),
+            fact_id("col", [a, m, c]),
+            fact_id("col", [b, n, d])
+        ])) <--
+            eqratio(a, m, b, n, m_prime, c, n_prime, d, ?_prov1),
+            col(a, m, c, ?_prov2),
+            col(b, n, d, ?_prov3),
+            if a != m && a != c &&
+               m != c &&
+               b != n && b != d &&
+               n != d &&
+               m == m_prime && n == n_prime,
+            provenance_settings(settings);
+
+        // Tangent and Two Chords
+        // Tangency is encoded as perp(o, p, p, t): line pt touches the circle centered o at p.
+        // If chords pa and pb subtend equal inscribed angles at c, they make equal angles with pt.
+        eqangle(t, p, a, b, p, t, settings.derive("tangent_two_chords", || vec![
+            fact_id("perp", [o, p, p, t]),
+            fact_id("cong", [o, p, o, a]),
+            fact_id("cong", [o, p, o, b]),
+            fact_id("cyclic", [p, a, b, c]),
+            fact_id("eqangle", [p, c, a, b, c, p])
+        ])) <--
+            perp(o, p, p_prime, t, ?_prov1),
+            cong(o, p, o_prime, a, ?_prov2),
+            cong(o, p, o_prime2, b, ?_prov3),
+            cyclic(p, a, b, c, ?_prov4),
+            eqangle(p, c, a, b, c_prime, p_prime2, ?_prov5),
+            if p == p_prime && o == o_prime && o == o_prime2 &&
+               c == c_prime && p == p_prime2 &&
+               o != p && o != t && p != t &&
+               a != b && a != c && a != p && a != t &&
+               b != c && b != p && b != t &&
+               c != p && c != t,
+            provenance_settings(settings);
+
+        // Midpoint Line Extension
+        // A point collinear with two of {a, m, b} is collinear with every pair of them
+        col(p, a, b, prov.clone()), col(p, m, b, prov) <--
+            midp(m, a, b, ?_prov1),
+            col(p, a, m, ?_prov2),
+            if a != b && m != a && m != b &&
+               p != a && p != m && p != b,
+            provenance_settings(settings),
+            let prov = settings.derive("midp_line_extend", || vec![
+                fact_id("midp", [m, a, b]),
+                fact_id("col", [p, a, m])
+            ]);
+
+        col(p, a, b, prov.clone()), col(p, a, m, prov) <--
+            midp(m, a, b, ?_prov1),
+            col(p, m, b, ?_prov2),
+            if a != b && m != a && m != b &&
+               p != a && p != m && p != b,
+            provenance_settings(settings),
+            let prov = settings.derive("midp_line_extend", || vec![
+                fact_id("midp", [m, a, b]),
+                fact_id("col", [p, m, b])
+            ]);
+
+        col(p, a, m, prov.clone()), col(p, m, b, prov) <--
+            midp(m, a, b, ?_prov1),
+            col(p, a, b, ?_prov2),
+            if a != b && m != a && m != b &&
+               p != a && p != m && p != b,
+            provenance_settings(settings),
+            let prov = settings.derive("midp_line_extend", || vec![
+                fact_id("midp", [m, a, b]),
+                fact_id("col", [p, a, b])
+            ]);
+
+        // Equal Constant Angles with a Transversal
+        // ∠xab and ∠xcd are the same constant angle measured from the common line xac
+        para(a, b, c, d, settings.derive("equal_aconst_para", || vec![
+            fact_id("aconst", [x.clone(), a.clone(), b.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("aconst", [x.clone(), c.clone(), d.clone(), m2.to_string(), n2.to_string()]),
+            fact_id("col", [x, a, c])
+        ])) <--
+            aconst(x, a, b, m1, n1, ?_prov1),
+            aconst(x, c, d, m2, n2, ?_prov2),
+            col(x, a, c, ?_prov3),
+            if *n1 != 0 && *n2 != 0 &&
+               normalize_angle(*m1, *n1) == normalize_angle(*m2, *n2) &&
+               x != a && x != b && x != c && x != d &&
+               a != b && a != c &&
+               c != d,
+            provenance_settings(settings);
+
+        // Equal Angles with a Transversal
+        // Lines ab and cd make the same directed angle with the transversal bc, ∠abc = ∠dcb,
+        // so they are parallel. If a, b, c were collinear both lines would be bc itself.
+        para(a, b, c, d, settings.derive("eqangle_para", || vec![
+            fact_id("eqangle", [a, b, c, d, c, b])
+        ])) <--
+            eqangle(a, b, c, d, c_prime, b_prime, ?_prov),
+            if b == b_prime && c == c_prime &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Parallels Cut by a Transversal
+        // Conversely, parallel lines ab and cd make equal directed angles with the transversal
+        // bc, which must not be either of them
+        eqangle(a, b, c, d, c, b, settings.derive("para_eqangle", || vec![
+            fact_id("para", [a, b, c, d])
+        ])) <--
+            para(a, b, c, d, ?_prov),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Equal Cevian Ratios
+        // Cevians ad and be of triangle abc cut bc and ac in the same ratio from c, so de ∥ ab
+        // and the cevians divide each other proportionally at their intersection p. The feet
+        // must lie inside the sides; with d beyond c the ratios say nothing about p.
+        eqratio(p, d, p, a, p, e, p, b, settings.derive("equal_cevian_ratios", || vec![
+            fact_id("col", [b, d, c]),
+            fact_id("col", [a, e, c]),
+            fact_id("eqratio", [c, d, d, b, c, e, e, a]),
+            fact_id("col", [a, p, d]),
+            fact_id("col", [b, p, e])
+        ])) <--
+            col(b, d, c, ?_prov1),
+            col(a, e, c, ?_prov2),
+            eqratio(c, d, d_prime, b, c_prime, e, e_prime, a, ?_prov3),
+            col(a, p, d, ?_prov4),
+            col(b, p, e, ?_prov5),
+            if c == c_prime && d == d_prime && e == e_prime &&
+               a != b && a != c && a != d && a != e && a != p &&
+               b != c && b != d && b != e && b != p &&
+               c != d && c != e && c != p &&
+               d != e && d != p &&
+               e != p,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(dx, dy, d), point(ex, ey, e),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()) &&
+               is_between((*dx, *dy), (*bx, *by), (*cx, *cy), eps.to_f64()) &&
+               is_between((*ex, *ey), (*ax, *ay), (*cx, *cy), eps.to_f64()),
+            provenance_settings(settings);
+
+        // Isosceles Trapezoid is Cyclic
+        // Convex abcd with ab ∥ cd and equal legs ad, bc; a parallelogram (ad ∥ bc) is excluded
+        cyclic(a, b, c, d, settings.derive("isosceles_trapezoid_cyclic", || vec![
+            fact_id("para", [a, b, c, d]),
+            fact_id("cong", [a, d, b, c])
+        ])) <--
+            para(a, b, c, d, ?_prov1),
+            cong(a, d, b, c, ?_prov2),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*cx, *cy), (*dx, *dy), (*ax, *ay)],
+                eps.to_f64()
+            ) == Some(true) && same_orientation(
+                vec![(*bx, *by), (*cx, *cy), (*dx, *dy)],
+                vec![(*dx, *dy), (*ax, *ay), (*bx, *by)],
+                eps.to_f64()
+            ) == Some(true) && check_numeric("para", &[(*ax, *ay), (*dx, *dy), (*bx, *by), (*cx, *cy)], eps.to_f64()) == Some(false),
+            provenance_settings(settings);
+
+        // Tangent-Chord on a Diameter
+        // pa is a diameter of the circle centered o and c is on the circle, so ∠pca is right;
+        // the tangent-chord angle ∠tpa equals it, making the tangent pt perpendicular to pa.
+        perp(t, p, p, a, settings.derive("tangent_chord_perp", || vec![
+            fact_id("eqangle", [t, p, a, p, c, a]),
+            fact_id("col", [p, o, a]),
+            fact_id("cong", [o, p, o, a]),
+            fact_id("cong", [o, p, o, c])
+        ])) <--
+            eqangle(t, p, a, p_prime, c, a_prime, ?_prov1),
+            col(p, o, a, ?_prov2),
+            cong(o, p, o_prime, a, ?_prov3),
+            cong(o, p, o_prime2, c, ?_prov4),
+            if p == p_prime && a == a_prime && o == o_prime && o == o_prime2 &&
+               a != c && a != o && a != p && a != t &&
+               c != o && c != p && c != t &&
+               o != p && o != t &&
+               p != t,
+            provenance_settings(settings);
+
+        // Midsegment Theorem
+        para(m, n, b, c, settings.derive("midsegment", || vec![
+            fact_id("midp", [m, a, b]),
+            fact_id("midp", [n, a, c])
+        ])) <--
+            midp(m, a, b, ?_prov1),
+            midp(n, a, c, ?_prov2),
+            if m != n && m != b && m != c &&
+               n != b && n != c &&
+               b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Similar Triangles in the Same Circle
+        // Both triangles share a circumradius, so equal angles subtend equal arcs and chords
+        contri1(a, b, c, d, e, f, settings.derive("equal_arc_triangles", || vec![
+            fact_id("simtri1", [a, b, c, d, e, f]),
+            fact_id("cyclic", [a, b, c, d]),
+            fact_id("cyclic", [a, b, c, e]),
+            fact_id("cyclic", [a, b, c, f])
+        ])) <--
+            simtri1(a, b, c, d, e, f, ?_prov1),
+            cyclic(a, b, c, d, ?_prov2),
+            cyclic(a, b, c, e, ?_prov3),
+            cyclic(a, b, c, f, ?_prov4),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f,
+            provenance_settings(settings);
+
+        contri2(a, b, c, d, e, f, settings.derive("equal_arc_triangles", || vec![
+            fact_id("simtri2", [a, b, c, d, e, f]),
+            fact_id("cyclic", [a, b, c, d]),
+            fact_id("cyclic", [a, b, c, e]),
+            fact_id("cyclic", [a, b, c, f])
+        ])) <--
+            simtri2(a, b, c, d, e, f, ?_prov1),
+            cyclic(a, b, c, d, ?_prov2),
+            cyclic(a, b, c, e, ?_prov3),
+            cyclic(a, b, c, f, ?_prov4),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f,
+            provenance_settings(settings);
+
+        // Common Parallel
+        // Two input lines parallel to a third input line are parallel. Only input facts are
+        // joined, so this is one step rather than the closure of `para_trans`. The first one is
+        // also read from para, which holds it as an axiom, so that the rule belongs to the main
+        // loop rather than a stratum of its own ahead of it.
+        para(a, b, c, d, settings.derive("common_parallel", || vec![
+            fact_id("para", [a, b, e, f]),
+            fact_id("para", [c, d, e, f])
+        ])) <--
+            para(a, b, e, f, ?_prov),
+            input_para(a, b, e, f),
+            input_para(c, d, e, f),
+            if a != b && c != d && e != f &&
+               !((a == c && b == d) || (a == d && b == c)) &&
+               !((a == e && b == f) || (a == f && b == e)) &&
+               !((c == e && d == f) || (c == f && d == e)),
+            provenance_settings(settings);
+
+        // Transitivity of Parallelism
+        // Facts are keyed on point names, so the closure over finitely many segments, symmetric
+        // variants included, reaches a fixpoint. It can be switched off to bound the blowup on
+        // dense inputs, leaving `common_parallel` to combine pairs of input facts only.
+        para(a, b, e, f, settings.derive("para_trans", || vec![
+            fact_id("para", [a, b, c, d]),
+            fact_id("para", [c, d, e, f])
+        ])) <--
+            !rule_disabled("para_trans".to_string()),
+            para(a, b, c, d, ?_prov1),
+            para(c, d, e, f, ?_prov2),
+            if a != b && c != d && e != f &&
+               !((a == c && b == d) || (a == d && b == c)) &&
+               !((a == e && b == f) || (a == f && b == e)) &&
+               !((c == e && d == f) || (c == f && d == e)),
+            provenance_settings(settings);
+
+        // Perpendicular to a Common Line
+        para(a, b, e, f, settings.derive("perp_perp_para", || vec![
+            fact_id("perp", [a, b, c, d]),
+            fact_id("perp", [e, f, c, d])
+        ])) <--
+            perp(a, b, c, d, ?_prov1),
+            perp(e, f, c, d, ?_prov2),
+            if a != b && c != d && e != f &&
+               !((a == e && b == f) || (a == f && b == e)),
+            provenance_settings(settings);
+
+        // Parallel to a Perpendicular
+        perp(a, b, e, f, settings.derive("para_perp_perp", || vec![
+            fact_id("para", [a, b, c, d]),
+            fact_id("perp", [c, d, e, f])
+        ])) <--
+            para(a, b, c, d, ?_prov1),
+            perp(c, d, e, f, ?_prov2),
+            if a != b && c != d && e != f &&
+               !((a == c && b == d) || (a == d && b == c)),
+            provenance_settings(settings);
+
+        // Transitivity of Equal Angles
+        eqangle(a, b, c, g, h, i, settings.derive("eqangle_trans", || vec![
+            fact_id("eqangle", [a, b, c, d, e, f]),
+            fact_id("eqangle", [d, e, f, g, h, i])
+        ])) <--
+            eqangle(a, b, c, d, e, f, ?_prov1),
+            eqangle(d, e, f, g, h, i, ?_prov2),
+            if a != b && b != c &&
+               d != e && e != f &&
+               g != h && h != i &&
+               (a, b, c) != (d, e, f) &&
+               (d, e, f) != (g, h, i) &&
+               (a, b, c) != (g, h, i),
+            provenance_settings(settings);
+
+        // Similar Triangles have Proportional Sides
+        // Vertices correspond in order for both orientations; only the angle directions flip
+        eqratio(a, b, d, e, b, c, e, f, prov.clone()),
+        eqratio(a, b, d, e, a, c, d, f, prov) <--
+            simtri1(a, b, c, d, e, f, ?_prov),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            provenance_settings(settings),
+            let prov = settings.derive("simtri_eqratio", || vec![fact_id("simtri1", [a, b, c, d, e, f])]);
+
+        eqratio(a, b, d, e, b, c, e, f, prov.clone()),
+        eqratio(a, b, d, e, a, c, d, f, prov) <--
+            simtri2(a, b, c, d, e, f, ?_prov),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            provenance_settings(settings),
+            let prov = settings.derive("simtri_eqratio", || vec![fact_id("simtri2", [a, b, c, d, e, f])]);
+
+        // Congruent Triangles have Congruent Sides
+        cong(a, b, d, e, prov.clone()),
+        cong(b, c, e, f, prov.clone()),
+        cong(a, c, d, f, prov) <--
+            contri1(a, b, c, d, e, f, ?_prov),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            provenance_settings(settings),
+            let prov = settings.derive("contri_cong", || vec![fact_id("contri1", [a, b, c, d, e, f])]);
+
+        cong(a, b, d, e, prov.clone()),
+        cong(b, c, e, f, prov.clone()),
+        cong(a, c, d, f, prov) <--
+            contri2(a, b, c, d, e, f, ?_prov),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            provenance_settings(settings),
+            let prov = settings.derive("contri_cong", || vec![fact_id("contri2", [a, b, c, d, e, f])]);
+
+        // Congruent Triangles have Equal Angles
+        // Opposite orientation reverses the direction of the corresponding angles
+        eqangle(b, a, c, e, d, f, prov.clone()),
+        eqangle(a, b, c, d, e, f, prov.clone()),
+        eqangle(b, c, a, e, f, d, prov) <--
+            contri1(a, b, c, d, e, f, ?_prov),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            provenance_settings(settings),
+            let prov = settings.derive("contri_eqangle", || vec![fact_id("contri1", [a, b, c, d, e, f])]);
+
+        eqangle(b, a, c, f, d, e, prov.clone()),
+        eqangle(a, b, c, f, e, d, prov.clone()),
+        eqangle(b, c, a, d, f, e, prov) <--
+            contri2(a, b, c, d, e, f, ?_prov),
+            if a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            provenance_settings(settings),
+            let prov = settings.derive("contri_eqangle", || vec![fact_id("contri2", [a, b, c, d, e, f])]);
+
+        // Congruent Triangles are Similar
+        simtri1(a, b, c, d, e, f, settings.derive("contri_simtri", || vec![fact_id("contri1", [a, b, c, d, e, f])])) <--
+            contri1(a, b, c, d, e, f, ?_prov),
+            if (a, b, c) != (d, e, f),
+            provenance_settings(settings);
+
+        simtri2(a, b, c, d, e, f, settings.derive("contri_simtri", || vec![fact_id("contri2", [a, b, c, d, e, f])])) <--
+            contri2(a, b, c, d, e, f, ?_prov),
+            if (a, b, c) != (d, e, f),
+            provenance_settings(settings);
+
+        // Converse of the Inscribed Angle Theorem
+        // c and d on the same side of ab see the chord ab under the same angle
+        cyclic(a, b, c, d, settings.derive("eqangle_cyclic", || vec![
+            fact_id("eqangle", [a, c, b, a, d, b])
+        ])) <--
+            eqangle(a, c, b, a_prime, d, b_prime, ?_prov),
+            if a == a_prime && b == b_prime &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*cx, *cy), (*bx, *by)],
+                vec![(*ax, *ay), (*dx, *dy), (*bx, *by)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings);
+
+        // Inscribed Angles on the Same Chord
+        eqangle(a, c, b, a, d, b, settings.derive("cyclic_eqangle", || vec![
+            fact_id("cyclic", [a, b, c, d])
+        ])) <--
+            cyclic(a, b, c, d, ?_prov),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            provenance_settings(settings);
+
+        // Collinearity through a Shared Pair
+        // a and b must be apart in the figure: two names for one point fix no line
+        col(b, c, d, settings.derive("col_trans", || vec![
+            fact_id("col", [a, b, c]),
+            fact_id("col", [a, b, d])
+        ])) <--
+            col(a, b, c, ?_prov1),
+            col(a, b, d, ?_prov2),
+            if a != b && c != d &&
+               c != a && c != b &&
+               d != a && d != b,
+            point(ax, ay, a), point(bx, by, b),
+            epsilon(eps),
+            if distinct_positions((*ax, *ay), (*bx, *by), eps.to_f64()),
+            provenance_settings(settings);
+
+        // Opposite Sides of a Parallelogram
+        cong(a, b, c, d, prov.clone()),
+        cong(a, d, b, c, prov) <--
+            para(a, b, c, d, ?_prov1),
+            para(a, d, b, c, ?_prov2),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*cx, *cy), (*dx, *dy), (*ax, *ay)],
+                eps.to_f64()
+            ) == Some(true),
+            provenance_settings(settings),
+            let prov = settings.derive("parallelogram_cong", || vec![
+                fact_id("para", [a, b, c, d]),
+                fact_id("para", [a, d, b, c])
+            ]);
+
+        // Diagonals of a Parallelogram Bisect Each Other
+        // m must already be a named point on both diagonals
+        midp(m, a, c, prov.clone()),
+        midp(m, b, d, prov) <--
+            para(a, b, c, d, ?_prov1),
+            para(a, d, b, c, ?_prov2),
+            col(a, m, c, ?_prov3),
+            col(b, m, d, ?_prov4),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d &&
+               m != a && m != b && m != c && m != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings),
+            let prov = settings.derive("parallelogram_diag_midp", || vec![
+                fact_id("para", [a, b, c, d]),
+                fact_id("para", [a, d, b, c]),
+                fact_id("col", [a, m, c]),
+                fact_id("col", [b, m, d])
+            ]);
+
+        // m may instead be known as the midpoint of diagonal ac, which makes it the common
+        // midpoint of both diagonals, cutting each into equal halves
+        midp(m, b, d, prov.clone()),
+        cong(a, m, m, c, prov.clone()),
+        cong(b, m, m, d, prov) <--
+            para(a, b, c, d, ?_prov1),
+            para(a, d, b, c, ?_prov2),
+            midp(m, a, c, ?_prov3),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d &&
+               m != a && m != b && m != c && m != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings),
+            let prov = settings.derive("parallelogram_diagonals", || vec![
+                fact_id("para", [a, b, c, d]),
+                fact_id("para", [a, d, b, c]),
+                fact_id("midp", [m, a, c])
+            ]);
+
+        // Transitivity of Equal Ratios
+        // Only chains segments written in name order; the symmetry rules restore every
+        // endpoint order, which keeps the number of derivations per fact manageable
+        eqratio(a, b, c, d, i, j, k, l, settings.derive("eqratio_trans", || vec![
+            fact_id("eqratio", [a, b, c, d, e, f, g, h]),
+            fact_id("eqratio", [e, f, g, h, i, j, k, l])
+        ])) <--
+            eqratio(a, b, c, d, e, f, g, h, ?_prov1),
+            if a < b && c < d && e < f && g < h,
+            eqratio(e, f, g, h, i, j, k, l, ?_prov2),
+            if i < j && k < l &&
+               !same_ratio([a, b, c, d], [e, f, g, h]) &&
+               !same_ratio([e, f, g, h], [i, j, k, l]) &&
+               !same_ratio([a, b, c, d], [i, j, k, l]),
+            provenance_settings(settings);
+
+        // Midpoint is Collinear
+        col(a, m, b, settings.derive("midp_col", || vec![fact_id("midp", [m, a, b])])) <--
+            midp(m, a, b, ?_prov),
+            if a != b && m != a && m != b,
+            provenance_settings(settings);
+
+        // Midpoint Halves the Segment
+        cong(a, m, b, m, settings.derive("midp_cong", || vec![fact_id("midp", [m, a, b])])) <--
+            midp(m, a, b, ?_prov),
+            if a != b && m != a && m != b,
+            provenance_settings(settings);
+
+        // Equidistant Collinear Point is the Midpoint
+        // o lies on bd and is as far from b as from d, so it is the midpoint of bd. Only the
+        // name order is derived; the symmetry rule states the other.
+        midp(o, b, d, settings.derive("col_cong_midp", || vec![
+            fact_id("col", [b, o, d]),
+            fact_id("cong", [o, b, o, d])
+        ])) <--
+            col(b, o, d, ?_prov1),
+            if b < d,
+            cong(o, b, o_prime, d, ?_prov2),
+            if o == o_prime && o != b && o != d,
+            provenance_settings(settings);
+
+        // Midpoint Uniqueness
+        // A segment has exactly one midpoint, so two midpoints of ab name the same point
+        eq(m, n, settings.derive("midp_unique", || vec![
+            fact_id("midp", [m, a, b]),
+            fact_id("midp", [n, a, b])
+        ])) <--
+            midp(m, a, b, ?_prov1),
+            midp(n, a, b, ?_prov2),
+            if a != b && m != n,
+            provenance_settings(settings);
+
+        // Median to the Hypotenuse
+        // The midpoint m of hypotenuse bc is equidistant from a, b, c, so am = bc / 2
+        cong(m, a, m, b, prov.clone()),
+        cong(m, a, m, c, prov.clone()),
+        eqratio(m, a, b, c, m, b, b, c, prov.clone()),
+        rconst(m, a, b, c, 1, 2, prov) <--
+            perp(b, a, a_prime, c, ?_prov1),
+            midp(m, b, c, ?_prov2),
+            if a == a_prime &&
+               a != b && a != c && a != m &&
+               b != c && b != m &&
+               c != m,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings),
+            let prov = settings.derive("median_half_hypotenuse", || vec![
+                fact_id("perp", [b, a, a, c]),
+                fact_id("midp", [m, b, c])
+            ]);
+
+        // Triangle Angle Sum
+        // Directed angles ∠abc + ∠bca + ∠cab of a triangle sum to a straight angle (0 mod π)
+        aconst(c, a, b, m, n, settings.derive("triangle_angle_sum", || vec![
+            fact_id("aconst", [a.clone(), b.clone(), c.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("aconst", [b.clone(), c.clone(), a.clone(), m2.to_string(), n2.to_string()])
+        ])) <--
+            aconst(a, b, c, m1, n1, ?_prov1),
+            aconst(b, c, a, m2, n2, ?_prov2),
+            if *n1 != 0 && *n2 != 0 &&
+               a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            let (m, n) = add_angles(-*m1, *n1, -*m2, *n2),
+            provenance_settings(settings);
+
+        // Exterior Angle
+        // d is on line bc, so the exterior angle ∠acd at c is -∠bca, which by the angle sum is
+        // the sum of the remote interior angles ∠abc + ∠cab
+        aconst(a, c, d, m, n, settings.derive("angle_sum", || vec![
+            fact_id("aconst", [a.clone(), b.clone(), c.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("aconst", [c.clone(), a.clone(), b.clone(), m2.to_string(), n2.to_string()]),
+            fact_id("col", [b, c, d])
+        ])) <--
+            aconst(a, b, c, m1, n1, ?_prov1),
+            aconst(c, a, b, m2, n2, ?_prov2),
+            col(b, c, d, ?_prov3),
+            if *n1 != 0 && *n2 != 0 &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            let (m, n) = add_angles(*m1, *n1, *m2, *n2),
+            provenance_settings(settings);
+
+        // Angle Addition
+        // Adjacent angles at b sharing the ray bc add up: ∠abc + ∠cbd = ∠abd
+        aconst(a, b, d, m, n, settings.derive("aconst_add", || vec![
+            fact_id("aconst", [a.clone(), b.clone(), c.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("aconst", [c.clone(), b.clone(), d.clone(), m2.to_string(), n2.to_string()])
+        ])) <--
+            aconst(a, b, c, m1, n1, ?_prov1),
+            aconst(c, b, d, m2, n2, ?_prov2),
+            if *n1 != 0 && *n2 != 0 &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            let (m, n) = add_angles(*m1, *n1, *m2, *n2),
+            provenance_settings(settings);
+
+        // Right Angle Constant
+        // aconst(a, b, c, m, n) is the directed angle from line ba to line bc, equal to mπ/n
+        // modulo π, so a right angle is (1, 2) in either direction
+        perp(b, a, b, c, settings.derive("aconst_perp", || vec![
+            fact_id("aconst", [a.clone(), b.clone(), c.clone(), m.to_string(), n.to_string()])
+        ])) <--
+            aconst(a, b, c, m, n, ?_prov),
+            if *n != 0 && normalize_angle(*m, *n) == (1, 2) &&
+               a != b && a != c && b != c,
+            provenance_settings(settings);
+
+        aconst(a, b, c, 1, 2, settings.derive("aconst_perp", || vec![
+            fact_id("perp", [b, a, b, c])
+        ])) <--
+            perp(b, a, b_prime, c, ?_prov),
+            if b == b_prime &&
+               a != b && a != c && b != c,
+            provenance_settings(settings);
+
+        // Supplementary Opposite Angles
+        // Interior angles at b and d summing to π read as equal directed angles ∠abc = ∠adc,
+        // with b and d on opposite sides of ac so that abcd is convex
+        cyclic(a, b, c, d, settings.derive("supplementary_opposite_cyclic", || vec![
+            fact_id("aconst", [a.clone(), b.clone(), c.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("aconst", [a_prime.clone(), d.clone(), c_prime.clone(), m2.to_string(), n2.to_string()])
+        ])) <--
+            aconst(a, b, c, m1, n1, ?_prov1),
+            aconst(a_prime, d, c_prime, m2, n2, ?_prov2),
+            if a == a_prime && c == c_prime &&
+               *n1 != 0 && *n2 != 0 &&
+               normalize_angle(*m1, *n1) == normalize_angle(*m2, *n2) &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
+                vec![(*ax, *ay), (*dx, *dy), (*cx, *cy)],
+                eps.to_f64()
+            ) == Some(false),
+            provenance_settings(settings);
+
+        // Incircle Radius to a Touch Point
+        // Incircle (or excircle) of abc centered i: i is on a bisector at b, eqangle(a,b,i,i,b,c),
+        // and touches ab at z, i.e. col(z,a,b) and perp(i,z,a,b). Then i is as far from bc as
+        // from ab, so a point x on bc with cong(i,z,i,x) is the touch point on bc.
+        perp(i, x, b, c, settings.derive("incircle_tangent", || vec![
+            fact_id("eqangle", [a, b, i, i, b, c]),
+            fact_id("perp", [i, z, a, b]),
+            fact_id("col", [z, a, b]),
+            fact_id("cong", [i, z, i, x]),
+            fact_id("col", [x, b, c])
+        ])) <--
+            eqangle(a, b, i, i_prime, b_prime, c, ?_prov1),
+            if i == i_prime && b == b_prime,
+            perp(i, z, a, b, ?_prov2),
+            col(z, a, b, ?_prov3),
+            cong(i, z, i_prime2, x, ?_prov4),
+            if i == i_prime2,
+            col(x, b, c, ?_prov5),
+            if a != b && a != c && b != c &&
+               i != a && i != b && i != c &&
+               z != a && z != b && z != i &&
+               x != b && x != c && x != i,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Equal Tangent Segments to the Incircle
+        // The incircle centered i touches ab at z and bc at x, so bz and bx are tangents from b:
+        // right triangles bzi and bxi share the hypotenuse bi and have equal legs iz = ix.
+        cong(b, z, b, x, settings.derive("incircle_tangent_segments", || vec![
+            fact_id("perp", [i, z, a, b]),
+            fact_id("col", [z, a, b]),
+            fact_id("perp", [i, x, b, c]),
+            fact_id("col", [x, b, c]),
+            fact_id("cong", [i, z, i, x])
+        ])) <--
+            perp(i, z, a, b, ?_prov1),
+            col(z, a, b, ?_prov2),
+            perp(i, x, b, c, ?_prov3),
+            col(x, b, c, ?_prov4),
+            cong(i, z, i_prime, x, ?_prov5),
+            if i == i_prime &&
+               a != b && a != c && b != c &&
+               i != b && i != z && i != x &&
+               z != b && x != b && z != x,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Tangent Parallel to a Chord
+        // The tangent pt is parallel to chord ab, so the radius op is perpendicular to ab and
+        // lies on its perpendicular bisector: p is the midpoint of arc ab and pa = pb.
+        cong(p, a, p, b, settings.derive("tangent_parallel_chord", || vec![
+            fact_id("perp", [o, p, p, t]),
+            fact_id("para", [p, t, a, b]),
+            fact_id("cong", [o, p, o, a]),
+            fact_id("cong", [o, p, o, b])
+        ])) <--
+            perp(o, p, p_prime, t, ?_prov1),
+            para(p, t, a, b, ?_prov2),
+            cong(o, p, o_prime, a, ?_prov3),
+            cong(o, p, o_prime2, b, ?_prov4),
+            if p == p_prime && o == o_prime && o == o_prime2 &&
+               o != p && o != t && p != t &&
+               a != b && a != p && a != t &&
+               b != p && b != t,
+            provenance_settings(settings);
+
+        // Tangent Angle and Central Angle
+        // Tangents pu and pv touch the circle at u and v, so ou ⊥ pu and ov ⊥ pv: the angle
+        // at p and the central angle uov are supplementary, i.e. equal as directed angles
+        aconst(u, o, v, m, n, settings.derive("tangent_angle_center", || vec![
+            fact_id("perp", [o, u, u, p]),
+            fact_id("perp", [o, v, v, p]),
+            fact_id("cong", [o, u, o, v]),
+            fact_id("aconst", [u.clone(), p.clone(), v.clone(), m.to_string(), n.to_string()])
+        ])) <--
+            perp(o, u, u_prime, p, ?_prov1),
+            perp(o, v, v_prime, p, ?_prov2),
+            cong(o, u, o_prime, v, ?_prov3),
+            aconst(u, p, v, m, n, ?_prov4),
+            if u == u_prime && v == v_prime && o == o_prime &&
+               o != u && o != v && o != p &&
+               u != v && u != p && v != p,
+            provenance_settings(settings);
+
+        perp(o, u, o, v, settings.derive("tangent_angle_center", || vec![
+            fact_id("perp", [o, u, u, p]),
+            fact_id("perp", [o, v, v, p]),
+            fact_id("cong", [o, u, o, v]),
+            fact_id("perp", [p, u, p, v])
+        ])) <--
+            perp(o, u, u_prime, p, ?_prov1),
+            perp(o, v, v_prime, p, ?_prov2),
+            cong(o, u, o_prime, v, ?_prov3),
+            perp(p, u, p_prime, v, ?_prov4),
+            if u == u_prime && v == v_prime && o == o_prime && p == p_prime &&
+               o != u && o != v && o != p &&
+               u != v && u != p && v != p,
+            provenance_settings(settings);
+
+        // Bisectors of Similar Triangles
+        // Internal bisectors ax and dy from corresponding vertices scale with the triangles
+        eqratio(a, x, d, y, a, b, d, e, settings.derive("similar_bisectors", || vec![
+            fact_id("simtri1", [a, b, c, d, e, f]),
+            fact_id("eqangle", [b, a, x, x, a, c]),
+            fact_id("col", [x, b, c]),
+            fact_id("eqangle", [e, d, y, y, d, f]),
+            fact_id("col", [y, e, f])
+        ])) <--
+            simtri1(a, b, c, d, e, f, ?_prov1),
+            eqangle(b, a, x, x_prime, a_prime, c, ?_prov2),
+            col(x, b, c, ?_prov3),
+            eqangle(e, d, y, y_prime, d_prime, f, ?_prov4),
+            col(y, e, f, ?_prov5),
+            if x == x_prime && a == a_prime && y == y_prime && d == d_prime &&
+               a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(ex, ey, e), point(fx, fy, f),
+            point(xx, xy, x), point(yx, yy, y),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()) &&
+               is_between((*xx, *xy), (*bx, *by), (*cx, *cy), eps.to_f64()) &&
+               is_between((*yx, *yy), (*ex, *ey), (*fx, *fy), eps.to_f64()),
+            provenance_settings(settings);
+
+        eqratio(a, x, d, y, a, b, d, e, settings.derive("similar_bisectors", || vec![
+            fact_id("simtri2", [a, b, c, d, e, f]),
+            fact_id("eqangle", [b, a, x, x, a, c]),
+            fact_id("col", [x, b, c]),
+            fact_id("eqangle", [e, d, y, y, d, f]),
+            fact_id("col", [y, e, f])
+        ])) <--
+            simtri2(a, b, c, d, e, f, ?_prov1),
+            eqangle(b, a, x, x_prime, a_prime, c, ?_prov2),
+            col(x, b, c, ?_prov3),
+            eqangle(e, d, y, y_prime, d_prime, f, ?_prov4),
+            col(y, e, f, ?_prov5),
+            if x == x_prime && a == a_prime && y == y_prime && d == d_prime &&
+               a != b && a != c && b != c &&
+               d != e && d != f && e != f &&
+               (a, b, c) != (d, e, f),
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(ex, ey, e), point(fx, fy, f),
+            point(xx, xy, x), point(yx, yy, y),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()) &&
+               is_between((*xx, *xy), (*bx, *by), (*cx, *cy), eps.to_f64()) &&
+               is_between((*yx, *yy), (*ex, *ey), (*fx, *fy), eps.to_f64()),
+            provenance_settings(settings);
+
+        // Unique Intersection of Two Lines
+        // Non-parallel lines ab and cd meet in a single point, so p and q, each claimed on
+        // both lines, name the same point
+        eq(p, q, settings.derive("unique_intersection", || vec![
+            fact_id("col", [p, a, b]),
+            fact_id("col", [p, c, d]),
+            fact_id("col", [q, a, b]),
+            fact_id("col", [q, c, d])
+        ])) <--
+            col(p, a, b, ?_prov1),
+            col(p, c, d, ?_prov2),
+            col(q, a, b, ?_prov3),
+            col(q, c, d, ?_prov4),
+            if a != b && c != d && p != q,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if check_numeric("para", &[(*ax, *ay), (*bx, *by), (*cx, *cy), (*dx, *dy)], eps.to_f64()) == Some(false),
+            provenance_settings(settings);
+
+        // Diagonals of a Rectangle
+        // abcd is a parallelogram (ab ∥ cd, ad ∥ bc, abc non-degenerate) with a right angle at b
+        cong(a, c, b, d, settings.derive("rectangle_diagonals", || vec![
+            fact_id("para", [a, b, c, d]),
+            fact_id("para", [a, d, b, c]),
+            fact_id("perp", [a, b, b, c])
+        ])) <--
+            para(a, b, c, d, ?_prov1),
+            para(a, d, b, c, ?_prov2),
+            perp(a, b, b_prime, c, ?_prov3),
+            if b == b_prime &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Rectangle is Cyclic
+        // Parallelogram abcd with a right angle at b; the diagonals' midpoint is the center
+        cyclic(a, b, c, d, settings.derive("rectangle_cyclic", || vec![
+            fact_id("para", [a, b, c, d]),
+            fact_id("para", [a, d, b, c]),
+            fact_id("perp", [a, b, b, c])
+        ])) <--
+            para(a, b, c, d, ?_prov1),
+            para(a, d, b, c, ?_prov2),
+            perp(a, b, b_prime, c, ?_prov3),
+            if b == b_prime &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Third Altitude
+        // Altitudes ad and be of triangle abc meet at the orthocenter h, which is on the third altitude
+        perp(c, h, a, b, settings.derive("third_altitude", || vec![
+            fact_id("perp", [a, d, b, c]),
+            fact_id("perp", [b, e, a, c]),
+            fact_id("col", [a, d, h]),
+            fact_id("col", [b, e, h])
+        ])) <--
+            perp(a, d, b, c, ?_prov1),
+            perp(b, e, a, c, ?_prov2),
+            col(a, d, h, ?_prov3),
+            col(b, e, h, ?_prov4),
+            if a != b && a != c && b != c &&
+               d != a && e != b &&
+               h != a && h != b && h != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Power of an External Point
+        // Secants pab and pcd from p outside the circle abcd satisfy pa·pb = pc·pd
+        eqratio(p, a, p, d, p, c, p, b, settings.derive("power_external_point", || vec![
+            fact_id("cyclic", [a, b, c, d]),
+            fact_id("col", [p, a, b]),
+            fact_id("col", [p, c, d])
+        ])) <--
+            cyclic(a, b, c, d, ?_prov1),
+            col(p, a, b, ?_prov2),
+            col(p, c, d, ?_prov3),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d &&
+               p != a && p != b && p != c && p != d,
+            point(px, py, p), point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if !is_between((*px, *py), (*ax, *ay), (*bx, *by), eps.to_f64()) &&
+               !is_between((*px, *py), (*cx, *cy), (*dx, *dy), eps.to_f64()),
+            provenance_settings(settings);
+
+        // Intersecting Chords
+        // Chords ab and cd of the circle abcd cross at p inside it, so pa·pb = pc·pd
+        eqratio(p, a, p, c, p, d, p, b, settings.derive("power_of_point", || vec![
+            fact_id("cyclic", [a, b, c, d]),
+            fact_id("col", [p, a, b]),
+            fact_id("col", [p, c, d])
+        ])) <--
+            cyclic(a, b, c, d, ?_prov1),
+            col(p, a, b, ?_prov2),
+            col(p, c, d, ?_prov3),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d &&
+               p != a && p != b && p != c && p != d,
+            point(px, py, p), point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if is_between((*px, *py), (*ax, *ay), (*bx, *by), eps.to_f64()) &&
+               is_between((*px, *py), (*cx, *cy), (*dx, *dy), eps.to_f64()),
+            provenance_settings(settings);
+
+        // Secant Triangles are Similar
+        // Secants pab and pcd from p outside the circle abcd share the angle at p and
+        // ∠pad = ∠pcb, so pad and pcb are similar with opposite orientation
+        simtri2(p, a, d, p, c, b, settings.derive("secant_triangles_sim", || vec![
+            fact_id("cyclic", [a, b, c, d]),
+            fact_id("col", [p, a, b]),
+            fact_id("col", [p, c, d])
+        ])) <--
+            cyclic(a, b, c, d, ?_prov1),
+            col(p, a, b, ?_prov2),
+            col(p, c, d, ?_prov3),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d &&
+               p != a && p != b && p != c && p != d,
+            point(px, py, p), point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if !is_between((*px, *py), (*ax, *ay), (*bx, *by), eps.to_f64()) &&
+               !is_between((*px, *py), (*cx, *cy), (*dx, *dy), eps.to_f64()) &&
+               same_orientation(
+                   vec![(*px, *py), (*ax, *ay), (*dx, *dy)],
+                   vec![(*px, *py), (*cx, *cy), (*bx, *by)],
+                   eps.to_f64()
+               ) == Some(false),
+            provenance_settings(settings);
+
+        // Tangent and Secant Triangles are Similar
+        // The tangent pt and the secant tac from t share the angle at t, and the tangent-chord
+        // angle ∠tpa equals the inscribed angle ∠pca = ∠tcp, so tpa and tcp are similar with
+        // opposite orientation
+        simtri2(t, p, a, t, c, p, settings.derive("tangent_inscribed_sim", || vec![
+            fact_id("perp", [o, p, p, t]),
+            fact_id("cong", [o, p, o, a]),
+            fact_id("cong", [o, p, o, c]),
+            fact_id("eqangle", [t, p, a, p, c, a]),
+            fact_id("col", [t, a, c])
+        ])) <--
+            perp(o, p, p_prime, t, ?_prov1),
+            cong(o, p, o_prime, a, ?_prov2),
+            cong(o, p, o_prime2, c, ?_prov3),
+            eqangle(t, p, a, p_prime2, c, a_prime, ?_prov4),
+            col(t, a, c, ?_prov5),
+            if p == p_prime && p == p_prime2 && a == a_prime &&
+               o == o_prime && o == o_prime2 &&
+               o != p && o != t && p != t &&
+               a != c && a != p && a != t &&
+               c != p && c != t,
+            point(tx, ty, t), point(px, py, p), point(ax, ay, a), point(cx, cy, c),
+            epsilon(eps),
+            if same_orientation(
+                vec![(*tx, *ty), (*px, *py), (*ax, *ay)],
+                vec![(*tx, *ty), (*cx, *cy), (*px, *py)],
+                eps.to_f64()
+            ) == Some(false),
+            provenance_settings(settings);
+
+        // Equal Constant Lengths
+        cong(a, b, c, d, settings.derive("lconst_cong", || vec![
+            fact_id("lconst", [a.clone(), b.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("lconst", [c.clone(), d.clone(), m2.to_string(), n2.to_string()])
+        ])) <--
+            lconst(a, b, m1, n1, ?_prov1),
+            lconst(c, d, m2, n2, ?_prov2),
+            if *n1 != 0 && *n2 != 0 &&
+               *m1 as i64 * *n2 as i64 == *m2 as i64 * *n1 as i64 &&
+               a != b && c != d &&
+               !((a == c && b == d) || (a == d && b == c)),
+            provenance_settings(settings);
+
+        // Newton-Gauss Line
+        // Opposite sides of quadrilateral abcd meet at e (ab, cd) and f (ad, bc); the midpoints
+        // of the diagonals ac, bd and of ef are collinear
+        col(m, n, k, settings.derive("diagonal_midpoints_col", || vec![
+            fact_id("col", [e, a, b]),
+            fact_id("col", [e, c, d]),
+            fact_id("col", [f, a, d]),
+            fact_id("col", [f, b, c]),
+            fact_id("midp", [m, a, c]),
+            fact_id("midp", [n, b, d]),
+            fact_id("midp", [k, e, f])
+        ])) <--
+            col(e, a, b, ?_prov1),
+            col(e, c, d, ?_prov2),
+            col(f, a, d, ?_prov3),
+            col(f, b, c, ?_prov4),
+            midp(m, a, c, ?_prov5),
+            midp(n, b, d, ?_prov6),
+            midp(k, e, f, ?_prov7),
+            if a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d &&
+               e != f && m != n && m != k && n != k,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c), point(dx, dy, d),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()) &&
+               is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*dx, *dy)], eps.to_f64()) &&
+               is_nondegenerate(vec![(*ax, *ay), (*cx, *cy), (*dx, *dy)], eps.to_f64()) &&
+               is_nondegenerate(vec![(*bx, *by), (*cx, *cy), (*dx, *dy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Equal Constant Ratios
+        eqratio(a, b, c, d, e, f, g, h, settings.derive("rconst_eqratio", || vec![
+            fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m1.to_string(), n1.to_string()]),
+            fact_id("rconst", [e.clone(), f.clone(), g.clone(), h.clone(), m2.to_string(), n2.to_string()])
+        ])) <--
+            rconst(a, b, c, d, m1, n1, ?_prov1),
+            rconst(e, f, g, h, m2, n2, ?_prov2),
+            if *n1 != 0 && *n2 != 0 &&
+               *m1 as i64 * *n2 as i64 == *m2 as i64 * *n1 as i64 &&
+               a != b && c != d && e != f && g != h &&
+               (a, b, c, d) != (e, f, g, h),
+            provenance_settings(settings);
+
+        // Unit Ratio
+        cong(a, b, c, d, settings.derive("rconst_cong", || vec![
+            fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()])
+        ])) <--
+            rconst(a, b, c, d, m, n, ?_prov),
+            if *n != 0 && m == n &&
+               a != b && c != d,
+            provenance_settings(settings);
+
+        // Reflection Preserves Angles
+        // x' is the reflection of x over line uv when u and v are both equidistant from x and x'
+        // (uv is the perpendicular bisector of xx'); line reflections reverse orientation.
+        line_reflection(u, v, x, x2) <--
+            cong(u, x, u_prime, x2, ?_prov1),
+            cong(v, x, v_prime, x2, ?_prov2),
+            if u == u_prime && v == v_prime &&
+               u != v && x != x2;
+
+        eqangle(a, b, c, c2, b2, a2, settings.derive("reflection_eqangle", || vec![
+            fact_id("cong", [u, a, u, a2]),
+            fact_id("cong", [v, a, v, a2]),
+            fact_id("cong", [u, b, u, b2]),
+            fact_id("cong", [v, b, v, b2]),
+            fact_id("cong", [u, c, u, c2]),
+            fact_id("cong", [v, c, v, c2])
+        ])) <--
+            line_reflection(u, v, a, a2),
+            line_reflection(u, v, b, b2),
+            line_reflection(u, v, c, c2),
+            if a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // A reflection through the point o (o is the midpoint of xx') keeps orientation
+        eqangle(a, b, c, a2, b2, c2, settings.derive("reflection_eqangle", || vec![
+            fact_id("midp", [o, a, a2]),
+            fact_id("midp", [o, b, b2]),
+            fact_id("midp", [o, c, c2])
+        ])) <--
+            midp(o, a, a2, ?_prov1),
+            midp(o, b, b2, ?_prov2),
+            midp(o, c, c2, ?_prov3),
+            if a != a2 && b != b2 && c != c2 &&
+               a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Perpendicular from the Circumcenter
+        // o is equidistant from b and c (in particular the circumcenter of any triangle on bc),
+        // so the foot m of the perpendicular from o to bc is the midpoint of bc
+        midp(m, b, c, prov.clone()),
+        cong(b, m, m, c, prov) <--
+            cong(o, b, o_prime, c, ?_prov1),
+            perp(o, m, b, c, ?_prov2),
+            col(b, m, c, ?_prov3),
+            if o == o_prime &&
+               b != c && o != m &&
+               m != b && m != c,
+            provenance_settings(settings),
+            let prov = settings.derive("circumcenter_perp_midpoint", || vec![
+                fact_id("cong", [o, b, o, c]),
+                fact_id("perp", [o, m, b, c]),
+                fact_id("col", [b, m, c])
+            ]);
+
+        // Ratio of a Bisected Chord
+        // The perpendicular from the center o bisects chord ab at m. circumcenter_perp_midpoint
+        // already gives am = mb; this states it as the unit ratio am/mb = ab/ab for rules that
+        // consume eqratio
+        eqratio(a, m, m, b, a, b, a, b, settings.derive("chord_bisection_ratio", || vec![
+            fact_id("cong", [o, a, o, b]),
+            fact_id("perp", [o, m, a, b]),
+            fact_id("col", [a, m, b])
+        ])) <--
+            cong(o, a, o_prime, b, ?_prov1),
+            perp(o, m, a, b, ?_prov2),
+            col(a, m, b, ?_prov3),
+            if o == o_prime &&
+               a != b && o != m &&
+               m != a && m != b,
+            provenance_settings(settings);
+
+        // Medial Triangle
+        // The medial triangle xyz is the image of abc under the homothety of ratio -1/2 about
+        // the centroid, a half-turn plus scaling, so the orientation is preserved
+        simtri1(x, y, z, a, b, c, settings.derive("medial_triangle_sim", || vec![
+            fact_id("midp", [x, b, c]),
+            fact_id("midp", [y, c, a]),
+            fact_id("midp", [z, a, b])
+        ])) <--
+            midp(x, b, c, ?_prov1),
+            midp(y, c, a, ?_prov2),
+            midp(z, a, b, ?_prov3),
+            if a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Isosceles Triangle Base Angles
+        // Legs ab and ac are equal, so the base angles at b and c are equal; as directed angles
+        // the equality reads ∠abc = ∠bca whatever the orientation of abc
+        eqangle(a, b, c, b, c, a, settings.derive("isosceles_base_angles", || vec![
+            fact_id("cong", [a, b, a, c])
+        ])) <--
+            cong(a, b, a_prime, c, ?_prov),
+            if a == a_prime &&
+               a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Converse: equal base angles at b and c make the legs ab and ac equal
+        cong(a, b, a, c, settings.derive("isosceles_equal_legs", || vec![
+            fact_id("eqangle", [a, b, c, b, c, a])
+        ])) <--
+            eqangle(a, b, c, b_prime, c_prime, a_prime, ?_prov),
+            if a == a_prime && b == b_prime && c == c_prime &&
+               a != b && a != c && b != c,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Perpendicular Bisector
+        // p and q are each equidistant from a and b, so line pq is the perpendicular bisector
+        // of ab; with the midpoint m of ab, line pm is
+        perp(p, q, a, b, settings.derive("perp_bisector", || vec![
+            fact_id("cong", [p, a, p, b]),
+            fact_id("cong", [q, a, q, b])
+        ])) <--
+            cong(p, a, p_prime, b, ?_prov1),
+            cong(q, a, q_prime, b, ?_prov2),
+            if p == p_prime && q == q_prime &&
+               a != b && a != p && a != q &&
+               b != p && b != q &&
+               p != q,
+            provenance_settings(settings);
+
+        perp(p, m, a, b, settings.derive("perp_bisector", || vec![
+            fact_id("cong", [p, a, p, b]),
+            fact_id("midp", [m, a, b])
+        ])) <--
+            midp(m, a, b, ?_prov1),
+            cong(p, a, p_prime, b, ?_prov2),
+            if p == p_prime &&
+               a != b && a != m && a != p &&
+               b != m && b != p &&
+               m != p,
+            provenance_settings(settings);
+
+        // Converse: a point p on the perpendicular to ab at its midpoint m is equidistant from a and b
+        cong(p, a, p, b, settings.derive("perp_bisector_cong", || vec![
+            fact_id("midp", [m, a, b]),
+            fact_id("perp", [p, m, a, b])
+        ])) <--
+            midp(m, a, b, ?_prov1),
+            perp(p, m, a, b, ?_prov2),
+            if a != b && a != m && a != p &&
+               b != m && b != p &&
+               m != p,
+            provenance_settings(settings);
+
+        // Circumcenter
+        // Congruence is not transitive in general, but radii of one circle are: oa = ob and
+        // ob = oc make o the circumcenter of abc, so oa = oc and every point at that distance
+        // from o is on the circle through a, b, c
+        cong(o, a, o, c, settings.derive("circumcenter", || vec![
+            fact_id("cong", [o, a, o, b]),
+            fact_id("cong", [o, b, o, c])
+        ])) <--
+            cong(o, a, o_prime, b, ?_prov1),
+            cong(o, b, o_prime2, c, ?_prov2),
+            if o == o_prime && o == o_prime2 &&
+               a != b && a != c && a != o &&
+               b != c && b != o &&
+               c != o,
+            provenance_settings(settings);
+
+        cyclic(a, b, c, d, settings.derive("circumcenter", || vec![
+            fact_id("cong", [o, a, o, b]),
+            fact_id("cong", [o, a, o, c]),
+            fact_id("cong", [o, a, o, d])
+        ])) <--
+            cong(o, a, o_prime, b, ?_prov1),
+            cong(o, a, o_prime2, c, ?_prov2),
+            cong(o, a, o_prime3, d, ?_prov3),
+            if o == o_prime && o == o_prime2 && o == o_prime3 &&
+               a != b && a != c && a != d && a != o &&
+               b != c && b != d && b != o &&
+               c != d && c != o &&
+               d != o,
+            provenance_settings(settings);
+
+        // Angle Bisector Theorem
+        // ad bisects the angle at a and meets bc at d, so d divides bc in the ratio of the
+        // adjacent sides: db / dc = ab / ac. The directed eqangle also holds for the external
+        // bisector, for which the theorem is the same.
+        eqratio(d, b, d, c, a, b, a, c, settings.derive("angle_bisector", || vec![
+            fact_id("eqangle", [b, a, d, d, a, c]),
+            fact_id("col", [b, d, c])
+        ])) <--
+            eqangle(b, a, d, d_prime, a_prime, c, ?_prov1),
+            col(b, d, c, ?_prov2),
+            if a == a_prime && d == d_prime &&
+               a != b && a != c && a != d &&
+               b != c && b != d &&
+               c != d,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Tangent is Perpendicular to the Radius
+        perp(o, p, a, b, settings.derive("tangent_perp", || vec![
+            fact_id("tangent", [a, b, o, p])
+        ])) <--
+            tangent(a, b, o, p, ?_prov),
+            if a != b && o != p,
+            provenance_settings(settings);
+
+        // The tangent point p is on line ab, so the radius is also perpendicular to pa. This is
+        // the perp(o, p, p, t) form of tangency that the other tangent rules match on.
+        perp(o, p, p, a, settings.derive("tangent_perp", || vec![
+            fact_id("tangent", [a, b, o, p])
+        ])) <--
+            tangent(a, b, o, p, ?_prov),
+            if a != b && a != p && b != p && o != p,
+            provenance_settings(settings);
+
+        // Tangent-Chord Angle
+        // The angle between the tangent pt and the chord pa equals the inscribed angle on pa at
+        // any other point c of the circle; as directed angles ∠tpa = ∠pca
+        eqangle(t, p, a, p, c, a, settings.derive("tangent_chord", || vec![
+            fact_id("tangent", [p, t, o, p]),
+            fact_id("cong", [o, p, o, a]),
+            fact_id("cong", [o, p, o, c])
+        ])) <--
+            tangent(p, t, o, p_prime, ?_prov1),
+            cong(o, p, o_prime, a, ?_prov2),
+            cong(o, p, o_prime2, c, ?_prov3),
+            if p == p_prime && o == o_prime && o == o_prime2 &&
+               a != c && a != o && a != p && a != t &&
+               c != o && c != p && c != t &&
+               o != p && o != t &&
+               p != t,
+            provenance_settings(settings);
+
+        // ----------------------------------------------------------------
+        // Opt-in Rules (enabled via `enable_rule`)
+        // ----------------------------------------------------------------
+
+        // Orthocenter Reflection
+        // h is the orthocenter of abc (ah ⟂ bc, bh ⟂ ac) and the altitude ah meets
+        // the circumcircle again at p, so p is the reflection of h over bc.
+        cong(b, h, b, p, prov.clone()), cong(c, h, c, p, prov) <--
+            rule_enabled("orthocenter_reflection".to_string()),
+            perp(a, h, b, c, ?_prov1),
+            perp(b, h, a, c, ?_prov2),
+            cyclic(a, b, c, p, ?_prov3),
+            col(a, h, p, ?_prov4),
+            if a != b && a != c && a != h && a != p &&
+               b != c && b != h && b != p &&
+               c != h && c != p &&
+               h != p,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings),
+            let prov = settings.derive("orthocenter_reflection", || vec![
+                fact_id("perp", [a, h, b, c]),
+                fact_id("perp", [b, h, a, c]),
+                fact_id("cyclic", [a, b, c, p]),
+                fact_id("col", [a, h, p])
+            ]);
+
+        // Collinear Pedal Points
+        // x, y, z are the feet of the perpendiculars from p to the sides of abc; when they are
+        // collinear in the figure (as on a Simson line) their collinearity is recorded.
+        // Off by default: it only restates what the coordinates already show.
+        col(x, y, z, settings.derive("pedal_collinear", || vec![
+            fact_id("perp", [p, x, b, c]),
+            fact_id("col", [x, b, c]),
+            fact_id("perp", [p, y, c, a]),
+            fact_id("col", [y, c, a]),
+            fact_id("perp", [p, z, a, b]),
+            fact_id("col", [z, a, b])
+        ])) <--
+            rule_enabled("pedal_collinear".to_string()),
+            perp(p, x, b, c, ?_prov1),
+            col(x, b, c, ?_prov2),
+            perp(p, y, c, a, ?_prov3),
+            col(y, c, a, ?_prov4),
+            perp(p, z, a, b, ?_prov5),
+            col(z, a, b, ?_prov6),
+            if a != b && a != c && b != c &&
+               x != y && x != z && y != z &&
+               p != x && p != y && p != z,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            point(xx, xy, x), point(yx, yy, y), point(zx, zy, z),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()) &&
+               !is_nondegenerate(vec![(*xx, *xy), (*yx, *yy), (*zx, *zy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Isogonal Cevians
+        // ad bisects ∠bac and cevian af is the reflection of cevian ae over it (∠dae = ∠fad),
+        // so the two cevians make equal angles with the sides: ∠bae = ∠fac.
+        eqangle(b, a, e, f, a, c, settings.derive("isogonal_cevian", || vec![
+            fact_id("eqangle", [b, a, d, d, a, c]),
+            fact_id("eqangle", [d, a, e, f, a, d]),
+            fact_id("col", [e, b, c]),
+            fact_id("col", [f, b, c])
+        ])) <--
+            rule_enabled("isogonal_cevian".to_string()),
+            eqangle(b, a, d, d_prime, a_prime, c, ?_prov1),
+            eqangle(d, a, e, f, a_prime2, d_prime2, ?_prov2),
+            col(e, b, c, ?_prov3),
+            col(f, b, c, ?_prov4),
+            if d == d_prime && a == a_prime && a == a_prime2 && d == d_prime2 &&
+               a != b && a != c && a != d && a != e && a != f &&
+               b != c && e != f,
+            point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
+            epsilon(eps),
+            if is_nondegenerate(vec![(*ax, *ay), (*bx, *by), (*cx, *cy)], eps.to_f64()),
+            provenance_settings(settings);
+
+        // Point Equality Substitution
+        // p and q name the same point, so every fact about p also holds for q. The rules
+        // carrying the facts over are generated by `eq_subst_rules`.
+        eq(p, r, settings.derive("eq_trans", || vec![
+            fact_id("eq", [p, q]),
+            fact_id("eq", [q, r])
+        ])) <--
+            rule_enabled("eq_substitution".to_string()),
+            eq(p, q, ?_prov1),
+            eq(q, r, ?_prov2),
+            if p != r,
+            provenance_settings(settings);
+
+        include_source!(eq_subst_rules);
+    }
+
+    impl AscentProgram {
+        // Iterations of the main loop, the first stratum, over every run of this program
+        pub fn main_loop_iterations(&self) -> usize {
+            self.scc_iters[0]
+        }
+    }
+}
+
+use program::AscentProgram;
+
+// A derived fact with named fields, as returned by `get_facts`; numeric args are stringified
+#[pyclass(get_all)]
+#[derive(Clone)]
+struct Fact {
+    predicate: String,
+    args: Vec<String>,
+    derivations: Vec<(String, Vec<String>)>,
+}
+
+#[pymethods]
+impl Fact {
+    fn __repr__(&self) -> String {
+        format!("Fact({}({}), {} derivations)", self.predicate, self.args.join(", "), self.derivations.len())
+    }
+}
+
+// Facts are stored as the plain tuples Python passes in and gets back
+#[pyclass]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[allow(clippy::type_complexity)]
+struct DeductiveDatabase {
+    // Input facts
+    points: Vec<(Coord, Coord, String)>,
+    col_facts: Vec<(String, String, String)>,
+    para_facts: Vec<(String, String, String, String)>,
+    perp_facts: Vec<(String, String, String, String)>,
+    cong_facts: Vec<(String, String, String, String)>,
+    eqangle_facts: Vec<(String, String, String, String, String, String)>,
+    cyclic_facts: Vec<(String, String, String, String)>,
+    sameclock_facts: Vec<(String, String, String, String, String, String)>,
+    midp_facts: Vec<(String, String, String)>,
+    contri1_facts: Vec<(String, String, String, String, String, String)>,
+    contri2_facts: Vec<(String, String, String, String, String, String)>,
+    simtri1_facts: Vec<(String, String, String, String, String, String)>,
+    simtri2_facts: Vec<(String, String, String, String, String, String)>,
+    eqratio_facts: Vec<(String, String, String, String, String, String, String, String)>,
+    aconst_facts: Vec<(String, String, String, i32, i32)>,
+    lconst_facts: Vec<(String, String, i32, i32)>,
+    rconst_facts: Vec<(String, String, String, String, i32, i32)>,
+    tangent_facts: Vec<(String, String, String, String)>,
+    // Canonical ids of the input facts, so an add_* call repeating a fact in any symmetric
+    // order is skipped; rebuilt from the facts when a database is loaded
+    #[serde(skip)]
+    input_ids: HashSet<String>,
+    // Facts read off the point coordinates by `infer_from_coordinates`, as (predicate, args)
+    coord_facts: Vec<(String, Vec<String>)>,
+
+    // Derived results with provenance
+    derived_col: Vec<(String, String, String, Provenance)>,
+    derived_para: Vec<(String, String, String, String, Provenance)>,
+    derived_perp: Vec<(String, String, String, String, Provenance)>,
+    derived_cong: Vec<(String, String, String, String, Provenance)>,
+    derived_eqangle: Vec<(String, String, String, String, String, String, Provenance)>,
+    derived_cyclic: Vec<(String, String, String, String, Provenance)>,
+    derived_sameclock: Vec<(String, String, String, String, String, String, Provenance)>,
+    derived_midp: Vec<(String, String, String, Provenance)>,
+    derived_contri1: Vec<(String, String, String, String, String, String, Provenance)>,
+    derived_contri2: Vec<(String, String, String, String, String, String, Provenance)>,
+    derived_simtri1: Vec<(String, String, String, String, String, String, Provenance)>,
+    derived_simtri2: Vec<(String, String, String, String, String, String, Provenance)>,
+    derived_eqratio: Vec<(String, String, String, String, String, String, String, String, Provenance)>,
+    derived_aconst: Vec<(String, String, String, i32, i32, Provenance)>,
+    derived_lconst: Vec<(String, String, i32, i32, Provenance)>,
+    derived_rconst: Vec<(String, String, String, String, i32, i32, Provenance)>,
+    derived_eq: Vec<(String, String, Provenance)>,
+    derived_tangent: Vec<(String, String, String, String, Provenance)>,
+    // Whether the last run reached its fixpoint
+    saturated: bool,
+    // Iterations of the main loop in the last run
+    #[serde(skip)]
+    iterations: usize,
+
+    // Opt-in rules that are disabled by default
+    enabled_rules: BTreeSet<String>,
+    // Default rules that were switched off
+    #[serde(default)]
+    disabled_rules: BTreeSet<String>,
+    // Most derivations kept per fact, unbounded if None
+    max_derivations_per_fact: Option<usize>,
+    // Most iterations of the main loop per run, unbounded if None
+    max_iterations: Option<usize>,
+    // Tolerance of the coordinate checks when points are floating-point, None for exact
+    // integer points
+    #[serde(default)]
+    epsilon: Option<f64>,
+
+    // Program saved by the last run for `run_incremental`
+    #[serde(skip)]
+    saved: SavedProgram,
+}
+
+// Saturated program along with the inputs it was seeded from. It is not serialized and a
+// cloned database starts without one, so its next incremental run is a full run.
+#[derive(Default)]
+struct SavedProgram(Option<(Box<AscentProgram>, Box<DeductiveDatabase>)>);
+
+impl Clone for SavedProgram {
+    fn clone(&self) -> Self {
+        SavedProgram(None)
+    }
+}
+
+// Seed a lattice relation of a saved program with new input facts as axioms. A fact that
+// was already derived gains the axiom derivation on its existing row, since a second row
+// with the same fact would be treated as a separate tuple.
+fn seed_axioms<F: Eq + Hash, R>(
+    rows: &mut Vec<R>,
+    settings: ProvenanceSettings,
+    facts: &[F],
+    split: impl Fn(&mut R) -> (F, &mut Provenance),
+    row: impl Fn(&F) -> R,
+) {
+    let mut existing: HashMap<F, usize> = HashMap::new();
+    for (i, r) in rows.iter_mut().enumerate() {
+        existing.insert(split(r).0, i);
+    }
+
+    for fact in facts {
+        match existing.get(fact) {
+            Some(&i) => {
+                split(&mut rows[i]).1.meet_mut(settings.axiom());
+            }
+            None => rows.push(row(fact)),
+        }
+    }
+}
+
+// Every orientation of the para facts ab ∥ cd: either line first, each with its points in
+// either order
+fn para_orientations(facts: &[(String, String, String, String)]) -> Vec<(String, String, String, String)> {
+    let mut rows = BTreeSet::new();
+    for (a, b, c, d) in facts {
+        for (l1, l2) in [((a, b), (c, d)), ((c, d), (a, b))] {
+            for (p, q) in [l1, (l1.1, l1.0)] {
+                for (r, s) in [l2, (l2.1, l2.0)] {
+                    rows.insert((p.clone(), q.clone(), r.clone(), s.clone()));
+                }
+            }
+        }
+    }
+    rows.into_iter().collect()
+}
+
+// Seed the trivial statements that hold by point existence alone: cong(a,b,a,b) and
+// para(a,b,a,b) for distinct a, b and eqangle(a,b,c,a,b,c) for distinct a, b, c, limited to
+// tuples naming a point from index `first_new` on. They are seeded here rather than derived
+// by rules so that the main loop is the first stratum of the program, which lets `saturate`
+// step it one iteration at a time.
+fn seed_trivial(
+    prog: &mut AscentProgram,
+    settings: ProvenanceSettings,
+    points: &[(Coord, Coord, String)],
+    first_new: usize,
+) {
+    let names: Vec<&String> = points.iter().map(|(_, _, name)| name).collect();
+    let mut pairs = Vec::new();
+    let mut triples = Vec::new();
+    for (i, a) in names.iter().enumerate() {
+        for (j, b) in names.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if i.max(j) >= first_new {
+                pairs.push(((*a).clone(), (*b).clone()));
+            }
+            for (k, c) in names.iter().enumerate() {
+                if k != i && k != j && i.max(j).max(k) >= first_new {
+                    triples.push(((*a).clone(), (*b).clone(), (*c).clone()));
+                }
+            }
+        }
+    }
+
+    seed_facts(&mut prog.cong, settings, "rfl", pairs.clone(),
+        |(a, b, c, d, p)| ((a == c && b == d).then(|| (a.clone(), b.clone())), p),
+        |(a, b)| (a.clone(), b.clone(), a, b, settings.derive("rfl", Vec::new)));
+    seed_facts(&mut prog.para, settings, "rfl", pairs,
+        |(a, b, c, d, p)| ((a == c && b == d).then(|| (a.clone(), b.clone())), p),
+        |(a, b)| (a.clone(), b.clone(), a, b, settings.derive("rfl", Vec::new)));
+    seed_facts(&mut prog.eqangle, settings, "rfl", triples,
+        |(a, b, c, d, e, f, p)| ((a == d && b == e && c == f).then(|| (a.clone(), b.clone(), c.clone())), p),
+        |(a, b, c)| (a.clone(), b.clone(), c.clone(), a, b, c, settings.derive("rfl", Vec::new)));
+}
+
+// Seed eqratio(a,b,c,d,a,b,c,d) for every two distinct declared segments ab and cd, those
+// named as segments by input facts (every pair of points would be O(n^4) facts). Unlike
+// seed_trivial this reseeds everything on an incremental run, as existing rows are merged.
+// perp and col get no trivial facts: a line is never perpendicular to itself, and col(a,a,b)
+// names a point twice, which every rule excludes.
+fn seed_trivial_eqratio(prog: &mut AscentProgram, settings: ProvenanceSettings, segments: &[(String, String)]) {
+    let mut pairs = Vec::new();
+    for (i, (a, b)) in segments.iter().enumerate() {
+        for (j, (c, d)) in segments.iter().enumerate() {
+            if i != j {
+                pairs.push((a.clone(), b.clone(), c.clone(), d.clone()));
+            }
+        }
+    }
+
+    seed_facts(&mut prog.eqratio, settings, "rfl", pairs,
+        |(a, b, c, d, e, f, g, h, p)| ((a == e && b == f && c == g && d == h).then(|| (a.clone(), b.clone(), c.clone(), d.clone())), p),
+        |(a, b, c, d)| (a.clone(), b.clone(), c.clone(), d.clone(), a, b, c, d, settings.derive("rfl", Vec::new)));
+}
+
+// Seed the facts found by `infer_from_coordinates`, each derived by the "coord" rule
+fn seed_coord_facts(prog: &mut AscentProgram, settings: ProvenanceSettings, facts: &[(String, Vec<String>)]) {
+    let of = |pred: &'static str| facts.iter().filter(move |(p, _)| p == pred).map(|(_, args)| args);
+    seed_facts(&mut prog.col, settings, "coord", of("col").map(|x| (x[0].clone(), x[1].clone(), x[2].clone())).collect(),
+        |(a, b, c, p)| (Some((a.clone(), b.clone(), c.clone())), p),
+        |(a, b, c)| (a, b, c, settings.derive("coord", Vec::new)));
+    seed_facts(&mut prog.midp, settings, "coord", of("midp").map(|x| (x[0].clone(), x[1].clone(), x[2].clone())).collect(),
+        |(a, b, c, p)| (Some((a.clone(), b.clone(), c.clone())), p),
+        |(a, b, c)| (a, b, c, settings.derive("coord", Vec::new)));
+    for (pred, rows) in [("para", &mut prog.para), ("perp", &mut prog.perp), ("cong", &mut prog.cong)] {
+        seed_facts(rows, settings, "coord", of(pred).map(|x| (x[0].clone(), x[1].clone(), x[2].clone(), x[3].clone())).collect(),
+            |(a, b, c, d, p)| (Some((a.clone(), b.clone(), c.clone(), d.clone())), p),
+            |(a, b, c, d)| (a, b, c, d, settings.derive("coord", Vec::new)));
+    }
+}
+
+// Seed a lattice relation with facts derived by `rule` from no premises, keyed by their free
+// arguments; `split` gives the key of a row stating such a fact (None for any other row) so
+// existing rows gain the derivation instead of being duplicated
+fn seed_facts<K: Eq + Hash, R>(
+    rows: &mut Vec<R>,
+    settings: ProvenanceSettings,
+    rule: &str,
+    facts: Vec<K>,
+    split: impl Fn(&mut R) -> (Option<K>, &mut Provenance),
+    row: impl Fn(K) -> R,
+) {
+    let mut existing: HashMap<K, usize> = HashMap::new();
+    for (i, r) in rows.iter_mut().enumerate() {
+        if let (Some(key), _) = split(r) {
+            existing.insert(key, i);
+        }
+    }
+
+    for fact in facts {
+        match existing.get(&fact) {
+            Some(&i) => {
+                split(&mut rows[i]).1.meet_mut(settings.derive(rule, Vec::new));
+            }
+            None => rows.push(row(fact)),
+        }
+    }
+}
+
+// The fact tuples of the Python API are spelled out in the signatures
+#[pymethods]
+#[allow(clippy::type_complexity)]
+impl DeductiveDatabase {
+    // With an epsilon, points take floating-point coordinates and coordinate checks treat
+    // values within epsilon of zero as zero; without one they are exact integers
+    #[new]
+    #[pyo3(signature = (epsilon=None))]
+    fn new(epsilon: Option<f64>) -> PyResult<Self> {
+        if epsilon.is_some_and(|eps| !(eps >= 0.0 && eps.is_finite())) {
+            return Err(PyValueError::new_err("epsilon must be a finite non-negative number"));
+        }
+        Ok(DeductiveDatabase { epsilon, ..Default::default() })
+    }
+
+    fn add_point(&mut self, x: Coord, y: Coord, name: String) -> PyResult<()> {
+        if self.points.iter().any(|(_, _, n)| n == &name) {
+            return Ok(());
+        }
+        let coord = |c: Coord| match (c, self.epsilon) {
+            (c, Some(_)) => Ok(Coord::Float(c.to_f64())),
+            (Coord::Float(v), None) if v.fract() == 0.0 && v.abs() < i64::MAX as f64 => Ok(Coord::Int(v as i64)),
+            (Coord::Float(v), None) => Err(PyValueError::new_err(format!(
+                "point {} has non-integer coordinate {}; construct the database with an epsilon for floating-point points",
+                name, v
+            ))),
+            (c, None) => Ok(c),
+        };
+        self.points.push((coord(x)?, coord(y)?, name.clone()));
+        Ok(())
+    }
+
+    fn add_col(&mut self, a: String, b: String, c: String) {
+        let id = fact_id("col", [&a, &b, &c]);
+        push_unique(&mut self.col_facts, &mut self.input_ids, id, (a, b, c));
+    }
+
+    fn add_para(&mut self, a: String, b: String, c: String, d: String) {
+        let id = fact_id("para", [&a, &b, &c, &d]);
+        push_unique(&mut self.para_facts, &mut self.input_ids, id, (a, b, c, d));
+    }
+
+    fn add_perp(&mut self, a: String, b: String, c: String, d: String) {
+        let id = fact_id("perp", [&a, &b, &c, &d]);
+        push_unique(&mut self.perp_facts, &mut self.input_ids, id, (a, b, c, d));
+    }
+
+    fn add_cong(&mut self, a: String, b: String, c: String, d: String) {
+        let id = fact_id("cong", [&a, &b, &c, &d]);
+        push_unique(&mut self.cong_facts, &mut self.input_ids, id, (a, b, c, d));
+    }
+
+    fn add_eqangle(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
+        let id = fact_id("eqangle", [&a, &b, &c, &d, &e, &f]);
+        push_unique(&mut self.eqangle_facts, &mut self.input_ids, id, (a, b, c, d, e, f));
+    }
+
+    fn add_cyclic(&mut self, a: String, b: String, c: String, d: String) {
+        let id = fact_id("cyclic", [&a, &b, &c, &d]);
+        push_unique(&mut self.cyclic_facts, &mut self.input_ids, id, (a, b, c, d));
+    }
+
+    fn add_sameclock(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
+        let id = fact_id("sameclock", [&a, &b, &c, &d, &e, &f]);
+        push_unique(&mut self.sameclock_facts, &mut self.input_ids, id, (a, b, c, d, e, f));
+    }
+
+    fn add_midp(&mut self, a: String, b: String, c: String) {
+        let id = fact_id("midp", [&a, &b, &c]);
+        push_unique(&mut self.midp_facts, &mut self.input_ids, id, (a, b, c));
+    }
+
+    // ab is a diameter of a circle centered o, stated as o being the midpoint of ab; a point p
+    // is on that circle given cong(o, a, o, p)
+    fn add_diameter(&mut self, o: String, a: String, b: String) {
+        let id = fact_id("midp", [&o, &a, &b]);
+        push_unique(&mut self.midp_facts, &mut self.input_ids, id, (o, a, b));
+    }
+
+    fn add_contri1(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
+        let id = fact_id("contri1", [&a, &b, &c, &d, &e, &f]);
+        push_unique(&mut self.contri1_facts, &mut self.input_ids, id, (a, b, c, d, e, f));
+    }
+
+    fn add_contri2(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
+        let id = fact_id("contri2", [&a, &b, &c, &d, &e, &f]);
+        push_unique(&mut self.contri2_facts, &mut self.input_ids, id, (a, b, c, d, e, f));
+    }
+
+    fn add_simtri1(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
+        let id = fact_id("simtri1", [&a, &b, &c, &d, &e, &f]);
+        push_unique(&mut self.simtri1_facts, &mut self.input_ids, id, (a, b, c, d, e, f));
+    }
+
+    fn add_simtri2(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
+        let id = fact_id("simtri2", [&a, &b, &c, &d, &e, &f]);
+        push_unique(&mut self.simtri2_facts, &mut self.input_ids, id, (a, b, c, d, e, f));
+    }
+
+    // One argument per point, like the other add_* methods
+    #[allow(clippy::too_many_arguments)]
+    fn add_eqratio(&mut self, a: String, b: String, c: String, d: String, e: String, f: String, g: String, h: String) {
+        let id = fact_id("eqratio", [&a, &b, &c, &d, &e, &f, &g, &h]);
+        push_unique(&mut self.eqratio_facts, &mut self.input_ids, id, (a, b, c, d, e, f, g, h));
+    }
+
+    fn add_aconst(&mut self, a: String, b: String, c: String, m: i32, n: i32) {
+        let id = fact_id("aconst", [a.clone(), b.clone(), c.clone(), m.to_string(), n.to_string()]);
+        push_unique(&mut self.aconst_facts, &mut self.input_ids, id, (a, b, c, m, n));
+    }
+
+    fn add_lconst(&mut self, a: String, b: String, m: i32, n: i32) {
+        let id = fact_id("lconst", [a.clone(), b.clone(), m.to_string(), n.to_string()]);
+        push_unique(&mut self.lconst_facts, &mut self.input_ids, id, (a, b, m, n));
+    }
+
+    fn add_rconst(&mut self, a: String, b: String, c: String, d: String, m: i32, n: i32) {
+        let id = fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()]);
+        push_unique(&mut self.rconst_facts, &mut self.input_ids, id, (a, b, c, d, m, n));
+    }
+
+    // Line ab is tangent at p to the circle centered o
+    fn add_tangent(&mut self, a: String, b: String, o: String, p: String) {
+        let id = fact_id("tangent", [&a, &b, &o, &p]);
+        push_unique(&mut self.tangent_facts, &mut self.input_ids, id, (a, b, o, p));
+    }
+
+    // Bulk versions of the add_* methods, taking every fact in one call to save per-call
+    // overhead on large problems; duplicates are skipped as in the single-fact methods
+    fn add_points(&mut self, points: Vec<(Coord, Coord, String)>) -> PyResult<()> {
+        for (x, y, name) in points {
+            self.add_point(x, y, name)?;
+        }
+        Ok(())
+    }
+
+    fn add_cols(&mut self, facts: Vec<(String, String, String)>) {
+        extend_unique(&mut self.col_facts, &mut self.input_ids, facts, |(a, b, c)| fact_id("col", [a, b, c]));
+    }
+
+    fn add_paras(&mut self, facts: Vec<(String, String, String, String)>) {
+        extend_unique(&mut self.para_facts, &mut self.input_ids, facts, |(a, b, c, d)| fact_id("para", [a, b, c, d]));
+    }
+
+    fn add_perps(&mut self, facts: Vec<(String, String, String, String)>) {
+        extend_unique(&mut self.perp_facts, &mut self.input_ids, facts, |(a, b, c, d)| fact_id("perp", [a, b, c, d]));
+    }
+
+    fn add_congs(&mut self, facts: Vec<(String, String, String, String)>) {
+        extend_unique(&mut self.cong_facts, &mut self.input_ids, facts, |(a, b, c, d)| fact_id("cong", [a, b, c, d]));
+    }
+
+    fn add_eqangles(&mut self, facts: Vec<(String, String, String, String, String, String)>) {
+        extend_unique(&mut self.eqangle_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f)| fact_id("eqangle", [a, b, c, d, e, f]));
+    }
+
+    fn add_cyclics(&mut self, facts: Vec<(String, String, String, String)>) {
+        extend_unique(&mut self.cyclic_facts, &mut self.input_ids, facts, |(a, b, c, d)| fact_id("cyclic", [a, b, c, d]));
+    }
+
+    fn add_sameclocks(&mut self, facts: Vec<(String, String, String, String, String, String)>) {
+        extend_unique(&mut self.sameclock_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f)| fact_id("sameclock", [a, b, c, d, e, f]));
+    }
+
+    fn add_midps(&mut self, facts: Vec<(String, String, String)>) {
+        extend_unique(&mut self.midp_facts, &mut self.input_ids, facts, |(a, b, c)| fact_id("midp", [a, b, c]));
+    }
+
+    fn add_contri1s(&mut self, facts: Vec<(String, String, String, String, String, String)>) {
+        extend_unique(&mut self.contri1_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f)| fact_id("contri1", [a, b, c, d, e, f]));
+    }
+
+    fn add_contri2s(&mut self, facts: Vec<(String, String, String, String, String, String)>) {
+        extend_unique(&mut self.contri2_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f)| fact_id("contri2", [a, b, c, d, e, f]));
+    }
+
+    fn add_simtri1s(&mut self, facts: Vec<(String, String, String, String, String, String)>) {
+        extend_unique(&mut self.simtri1_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f)| fact_id("simtri1", [a, b, c, d, e, f]));
+    }
+
+    fn add_simtri2s(&mut self, facts: Vec<(String, String, String, String, String, String)>) {
+        extend_unique(&mut self.simtri2_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f)| fact_id("simtri2", [a, b, c, d, e, f]));
+    }
+
+    fn add_eqratios(&mut self, facts: Vec<(String, String, String, String, String, String, String, String)>) {
+        extend_unique(&mut self.eqratio_facts, &mut self.input_ids, facts, |(a, b, c, d, e, f, g, h)| fact_id("eqratio", [a, b, c, d, e, f, g, h]));
+    }
+
+    fn add_aconsts(&mut self, facts: Vec<(String, String, String, i32, i32)>) {
+        extend_unique(&mut self.aconst_facts, &mut self.input_ids, facts, |(a, b, c, m, n)| fact_id("aconst", [a.clone(), b.clone(), c.clone(), m.to_string(), n.to_string()]));
+    }
+
+    fn add_lconsts(&mut self, facts: Vec<(String, String, i32, i32)>) {
+        extend_unique(&mut self.lconst_facts, &mut self.input_ids, facts, |(a, b, m, n)| fact_id("lconst", [a.clone(), b.clone(), m.to_string(), n.to_string()]));
+    }
+
+    fn add_rconsts(&mut self, facts: Vec<(String, String, String, String, i32, i32)>) {
+        extend_unique(&mut self.rconst_facts, &mut self.input_ids, facts, |(a, b, c, d, m, n)| fact_id("rconst", [a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()]));
+    }
+
+    fn add_tangents(&mut self, facts: Vec<(String, String, String, String)>) {
+        extend_unique(&mut self.tangent_facts, &mut self.input_ids, facts, |(a, b, o, p)| fact_id("tangent", [a, b, o, p]));
+    }
+
+    // Enable an opt-in rule, or switch a disabled default rule back on
+    fn enable_rule(&mut self, rule: String) {
+        self.disabled_rules.remove(&rule);
+        self.enabled_rules.insert(rule);
+    }
+
+    // Disable an opt-in rule, or switch off a default rule that allows it (`para_trans`, the
+    // transitivity of parallelism, leaving `common_parallel` over the input facts)
+    fn disable_rule(&mut self, rule: String) {
+        self.enabled_rules.remove(&rule);
+        self.disabled_rules.insert(rule);
+    }
+
+    // Empty the database of points, input facts and derived facts, keeping the rule settings
+    fn clear(&mut self) {
+        *self = DeductiveDatabase {
+            enabled_rules: std::mem::take(&mut self.enabled_rules),
+            disabled_rules: std::mem::take(&mut self.disabled_rules),
+            max_derivations_per_fact: self.max_derivations_per_fact,
+            max_iterations: self.max_iterations,
+            epsilon: self.epsilon,
+            ..Default::default()
+        };
+    }
+
+    // Drop the derived facts and the saved program, keeping the points and input facts
+    fn clear_derived(&mut self) {
+        *self = self.without_derived();
+    }
+
+    // Bound the derivations recorded per fact to keep dense inputs tractable; None removes
+    // the bound. Every derived fact keeps at least one derivation.
+    #[pyo3(signature = (cap))]
+    fn set_max_derivations_per_fact(&mut self, cap: Option<usize>) -> PyResult<()> {
+        if cap == Some(0) {
+            return Err(PyValueError::new_err("max_derivations_per_fact must be at least 1"));
+        }
+        self.max_derivations_per_fact = cap;
+        Ok(())
+    }
+
+    // Stop each run after this many iterations of the main loop, trading completeness for
+    // time on explosive inputs; None runs to the fixpoint. See `saturated`.
+    #[pyo3(signature = (max))]
+    fn set_max_iterations(&mut self, max: Option<usize>) -> PyResult<()> {
+        if max == Some(0) {
+            return Err(PyValueError::new_err("max_iterations must be at least 1"));
+        }
+        self.max_iterations = max;
+        Ok(())
+    }
+
+    // Read the col, para, perp, cong and midp facts that hold for the current points off
+    // their coordinates, to be seeded by every later run in place of listing them as input
+    // facts. Replaces the facts of an earlier call; returns how many were found.
+    fn infer_from_coordinates(&mut self) -> PyResult<usize> {
+        let n = self.points.len();
+        if n > MAX_INFERRED_POINTS {
+            return Err(PyValueError::new_err(format!(
+                "infer_from_coordinates reads at most {} points, got {}",
+                MAX_INFERRED_POINTS, n
+            )));
+        }
+
+        let eps = self.epsilon.unwrap_or(0.0);
+        let xy = |i: usize| (self.points[i].0, self.points[i].1);
+        let name = |i: usize| self.points[i].2.clone();
+        let mut facts = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                for k in j + 1..n {
+                    if check_numeric("col", &[xy(i), xy(j), xy(k)], eps) == Some(true) {
+                        facts.push(("col".to_string(), vec![name(i), name(j), name(k)]));
+                    }
+                }
+                for m in (0..n).filter(|&m| m != i && m != j) {
+                    if check_numeric("midp", &[xy(m), xy(i), xy(j)], eps) == Some(true) {
+                        facts.push(("midp".to_string(), vec![name(m), name(i), name(j)]));
+                    }
+                }
+            }
+        }
+
+        // Segments between distinct positions; two segments on one line are left to col
+        let segments: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| distinct_positions(xy(i), xy(j), eps))
+            .collect();
+        for (s, &(a, b)) in segments.iter().enumerate() {
+            for &(c, d) in &segments[s + 1..] {
+                let p = [xy(a), xy(b), xy(c), xy(d)];
+                let args = || vec![name(a), name(b), name(c), name(d)];
+                let same_line = check_numeric("col", &[p[0], p[1], p[2]], eps) == Some(true)
+                    && check_numeric("col", &[p[0], p[1], p[3]], eps) == Some(true);
+                if !same_line && check_numeric("para", &p, eps) == Some(true) {
+                    facts.push(("para".to_string(), args()));
+                }
+                for pred in ["perp", "cong"] {
+                    if check_numeric(pred, &p, eps) == Some(true) {
+                        facts.push((pred.to_string(), args()));
+                    }
+                }
+            }
+        }
+
+        self.coord_facts = facts;
+        Ok(self.coord_facts.len())
+    }
+
+    // Whether the last run reached its fixpoint rather than stopping at `max_iterations` or
+    // the `run_with_timeout` budget
+    fn saturated(&self) -> bool {
+        self.saturated
+    }
+
+    fn run(&mut self) {
+        self.run_until(None, true);
+    }
+
+    // Run like `run` but stop after `millis` milliseconds, keeping the facts derived so far.
+    // Returns whether the fixpoint was reached, as `saturated` does afterwards.
+    fn run_with_timeout(&mut self, millis: u64) -> bool {
+        self.run_until(Some(Instant::now() + Duration::from_millis(millis)), true);
+        self.saturated
+    }
+
+    // Run without recording derivations, for callers that only ask whether facts hold. This
+    // is not a separate program of plain relations: the same lattice relations and joins run,
+    // and only the derivation sets are skipped. Each stays empty, so a fact never changes once
+    // derived and no parent ids are built. The has_* queries work as usual, while every
+    // derivation list (get_*, explain_*, proofs) is empty. No program is saved, so
+    // `run_incremental` then runs fully.
+    fn run_without_provenance(&mut self) {
+        self.run_until(None, false);
+        self.saved = SavedProgram::default();
+    }
+
+    // Continue from the program saved by the last run, seeding only the input facts added
+    // since then. This is not a propagation of the new facts alone: ascent moves every stored
+    // row into the delta when a run starts, so the first iteration joins all rows again and
+    // can take longer than a whole run from the inputs. What is saved is the re-seeding and
+    // the iterations that reached the old fixpoint; after the first iteration only
+    // consequences of the new facts are left to derive. Falls back to a full run when there
+    // is no saved program or the settings or coordinate facts changed, as a rule or fact
+    // cannot be taken back from a saturated program.
+    fn run_incremental(&mut self) {
+        let Some((mut prog, seen)) = self.saved.0.take() else {
+            return self.run();
+        };
+        if seen.enabled_rules != self.enabled_rules ||
+           seen.disabled_rules != self.disabled_rules ||
+           seen.max_derivations_per_fact != self.max_derivations_per_fact ||
+           seen.max_iterations != self.max_iterations ||
+           seen.coord_facts != self.coord_facts {
+            return self.run();
+        }
+
+        self.seed_inputs(&mut prog, self.provenance_settings(true), &seen);
+        self.saturate(*prog, self.without_derived(), None);
+    }
+
+    #[pyo3(signature = (include_derived = true))]
+    fn to_bincode<'py>(&self, py: Python<'py>, include_derived: bool) -> PyResult<Bound<'py, PyBytes>> {
+        let encoded = if include_derived {
+            bincode::serialize(self)
+        } else {
+            bincode::serialize(&self.without_derived())
+        }
+        .map_err(|e| PyValueError::new_err(format!("failed to serialize database: {}", e)))?;
+
+        let mut data = Vec::with_capacity(encoded.len() + 1);
+        data.push(BINCODE_FORMAT_VERSION);
+        data.extend(encoded);
+        Ok(PyBytes::new_bound(py, &data))
+    }
 
-fn same_orientation(l1: Vec<(i64, i64)>, l2: Vec<(i64, i64)>) -> bool {
-    let edge_length = |p: (i64, i64), q: (i64, i64)| (q.0 - p.0) * (q.1 + p.1);
+    #[staticmethod]
+    fn from_bincode(data: &[u8]) -> PyResult<Self> {
+        match data.split_first() {
+            Some((&BINCODE_FORMAT_VERSION, rest)) => bincode::deserialize(rest)
+                .map(DeductiveDatabase::with_input_ids)
+                .map_err(|e| PyValueError::new_err(format!("failed to deserialize database: {}", e))),
+            Some((version, _)) => Err(PyValueError::new_err(format!(
+                "unsupported bincode format version {} (expected {})",
+                version, BINCODE_FORMAT_VERSION
+            ))),
+            None => Err(PyValueError::new_err("empty bincode data")),
+        }
+    }
 
-    let area1: i64 = (0..l1.len())
-        .map(|i| edge_length(l1[i].clone(), l1[(i + 1) % l1.len()].clone()))
-        .sum();
+    // Whole database as JSON: points, input facts, settings and derived facts with provenance,
+    // so a solved problem can be cached and reloaded without running the fixpoint again
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| PyValueError::new_err(format!("failed to serialize database: {}", e)))
+    }
 
-    let area2: i64 = (0..l2.len())
-        .map(|i| edge_length(l2[i].clone(), l2[(i + 1) % l2.len()].clone()))
-        .sum();
+    #[staticmethod]
+    fn from_json(s: String) -> PyResult<Self> {
+        serde_json::from_str(&s)
+            .map(DeductiveDatabase::with_input_ids)
+            .map_err(|e| PyValueError::new_err(format!("failed to deserialize database: {}", e)))
+    }
 
-    (area1 * area2) > 0
-}
+    // Canonical form of a fact id, the one used for provenance parents and proof lookups
+    #[staticmethod]
+    fn canonical_fact_id(fact_id: String) -> String {
+        canonical_fact_id(&fact_id)
+    }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Derivation {
-    rule: String,
-    parents: BTreeSet<String>,
-}
+    // Counts from the last run as {"relations": {predicate: derived tuples}, "rules": {rule:
+    // recorded derivations}, "iterations": main loop iterations}, showing which theorems do
+    // the work and which ones run away
+    fn stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let mut relations: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut rules: BTreeMap<&str, usize> = BTreeMap::new();
+        for (pred, _, prov) in self.derived_facts() {
+            *relations.entry(pred).or_default() += 1;
+            for derivation in &prov.derivations {
+                *rules.entry(derivation.rule.as_str()).or_default() += 1;
+            }
+        }
 
-impl Derivation {
-    fn axiom() -> Self {
-        Derivation {
-            rule: "axiom".to_string(),
-            parents: BTreeSet::new(),
+        let stats = PyDict::new_bound(py);
+        stats.set_item("relations", relations)?;
+        stats.set_item("rules", rules)?;
+        stats.set_item("iterations", self.iterations)?;
+        Ok(stats.into_any().unbind())
+    }
+
+    // Per predicate: (name, numerically true facts over all points, how many of those were derived).
+    // Facts are ordered tuples of point names; segments and angle arms must be non-degenerate.
+    fn coverage(&self) -> PyResult<Vec<(String, usize, usize)>> {
+        let n = self.points.len();
+        if n > MAX_COVERAGE_POINTS {
+            return Err(PyValueError::new_err(format!(
+                "coverage enumerates at most {} points, got {}",
+                MAX_COVERAGE_POINTS, n
+            )));
+        }
+
+        let eps = self.epsilon.unwrap_or(0.0);
+        let derived: HashSet<(&str, Vec<String>)> = self.derived_facts()
+            .into_iter()
+            .map(|(pred, args, _)| (pred, args))
+            .collect();
+
+        let mut result = Vec::new();
+        for (pred, arity) in NUMERIC_PREDICATES {
+            let mut true_count = 0;
+            let mut derived_count = 0;
+            let mut idx = vec![0; arity];
+
+            'tuples: loop {
+                let distinct = |i: usize, j: usize| idx[i] != idx[j];
+                let valid = match pred {
+                    "col" | "midp" => distinct(0, 1) && distinct(0, 2) && distinct(1, 2),
+                    "cyclic" => (0..4).all(|i| (i + 1..4).all(|j| distinct(i, j))),
+                    "eqangle" => distinct(0, 1) && distinct(1, 2) && distinct(3, 4) && distinct(4, 5),
+                    _ => distinct(0, 1) && distinct(2, 3),
+                };
+
+                if valid {
+                    let coords: Vec<(Coord, Coord)> = idx.iter().map(|&i| (self.points[i].0, self.points[i].1)).collect();
+                    if check_numeric(pred, &coords, eps) == Some(true) {
+                        true_count += 1;
+                        let args = idx.iter().map(|&i| self.points[i].2.clone()).collect();
+                        if derived.contains(&(pred, args)) {
+                            derived_count += 1;
+                        }
+                    }
+                }
+
+                // Advance to the next tuple of point indices
+                for slot in (0..arity).rev() {
+                    idx[slot] += 1;
+                    if idx[slot] < n {
+                        continue 'tuples;
+                    }
+                    idx[slot] = 0;
+                }
+                break;
+            }
+
+            result.push((pred.to_string(), true_count, derived_count));
         }
+
+        Ok(result)
     }
 
-    fn new(rule: &str, parents: Vec<String>) -> Self {
-        Derivation {
-            rule: rule.to_string(),
-            parents: parents.into_iter().collect(),
+    // Pairs of derived fact ids that cannot both hold, showing the input facts are
+    // inconsistent: lines both parallel and perpendicular, and congruences the point
+    // coordinates refute, paired with "coordinates". Each pair is reported once.
+    fn contradictions(&self) -> Vec<(String, String)> {
+        let paras: HashSet<String> = self.derived_para.iter()
+            .map(|(a, b, c, d, _)| fact_id("para", [a, b, c, d]))
+            .collect();
+        let mut conflicts = BTreeSet::new();
+        for (a, b, c, d, _) in &self.derived_perp {
+            let para = fact_id("para", [a, b, c, d]);
+            if a != b && c != d && paras.contains(&para) {
+                conflicts.insert((para, fact_id("perp", [a, b, c, d])));
+            }
+        }
+
+        for (a, b, c, d, _) in &self.derived_cong {
+            if self.numeric_value("cong", &[a.clone(), b.clone(), c.clone(), d.clone()]) == Some(false) {
+                conflicts.insert((fact_id("cong", [a, b, c, d]), "coordinates".to_string()));
+            }
         }
+        conflicts.into_iter().collect()
     }
-}
 
-// Provenance lattice to track all ways a fact was derived
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Provenance {
-    derivations: BTreeSet<Derivation>,
-}
+    // Ids of derived col, para, perp, cong and midp facts that are false for the point
+    // coordinates, which points at a faulty rule or inconsistent input facts
+    fn verify(&self) -> Vec<String> {
+        let mut wrong = BTreeSet::new();
+        for (pred, args, _) in self.derived_facts() {
+            if ["col", "para", "perp", "cong", "midp"].contains(&pred)
+                && self.numeric_value(pred, &args) == Some(false)
+            {
+                wrong.insert(fact_id(pred, &args));
+            }
+        }
+        wrong.into_iter().collect()
+    }
 
-impl Provenance {
-    fn axiom() -> Self {
-        let mut derivations = BTreeSet::new();
-        derivations.insert(Derivation::axiom());
-        Provenance { derivations }
+    // Proof text of two facts believed to conflict, so their derivations can be compared
+    fn explain_contradiction(&self, fact1: (String, Vec<String>), fact2: (String, Vec<String>)) -> (String, String) {
+        let depths = self.proof_depths();
+        let explain = |(pred, args): (String, Vec<String>)| {
+            let id = fact_id(&pred, args);
+            self.proof_text(&depths, &id)
+                .unwrap_or_else(|| format!("{} was not derived", id))
+        };
+        (explain(fact1), explain(fact2))
     }
 
-    fn from(rule: &str, parents: Vec<String>) -> Self {
-        let mut derivations = BTreeSet::new();
-        derivations.insert(Derivation::new(rule, parents));
-        Provenance { derivations }
+    // Proof of a fact as a DAG of (fact_id, rule, parent_fact_ids) nodes. Every fact uses its
+    // minimum-depth derivation, so parents are strictly shallower and the graph is acyclic;
+    // shared subproofs appear once. Nodes are ordered parents first; empty if not derived.
+    fn proof_dag(&self, pred: String, args: Vec<String>) -> Vec<(String, String, Vec<String>)> {
+        let depths = self.proof_depths();
+        let id = fact_id(&pred, args);
+        self.proof_steps(&depths, &id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| {
+                let derivation = depths[f].1;
+                (f.to_string(), derivation.rule.clone(), derivation.parents.iter().cloned().collect())
+            })
+            .collect()
     }
-}
 
-impl Lattice for Provenance {
-    fn meet(self, other: Self) -> Self {
-        let mut derivations = self.derivations;
-        derivations.extend(other.derivations);
-        Provenance { derivations }
+    // Graphviz DOT graph of every derivation: one node per fact (axioms filled grey) and one
+    // small node per derivation labeled with its rule, with edges from each premise to the
+    // rule node and from the rule node to the conclusion. Symmetry steps that restate a fact
+    // under its own canonical id are left out.
+    fn to_dot(&self) -> String {
+        let mut facts: BTreeMap<String, BTreeSet<&Derivation>> = BTreeMap::new();
+        for (pred, args, prov) in self.derived_facts() {
+            facts.entry(fact_id(pred, args)).or_default().extend(&prov.derivations);
+        }
+
+        let mut lines = vec!["digraph proof {".to_string(), "    rankdir=LR;".to_string()];
+        let mut rule_nodes = 0;
+        for (id, derivations) in &facts {
+            if derivations.iter().any(|d| d.rule == "axiom") {
+                lines.push(format!("    {:?} [shape=box, style=filled, fillcolor=lightgrey];", id));
+            } else {
+                lines.push(format!("    {:?} [shape=box];", id));
+            }
+
+            for derivation in derivations {
+                if derivation.rule == "axiom" || derivation.parents.contains(id) {
+                    continue;
+                }
+                rule_nodes += 1;
+                let node = format!("r{}", rule_nodes);
+                lines.push(format!("    {} [label={:?}, shape=ellipse, fontsize=10];", node, derivation.rule));
+                for parent in &derivation.parents {
+                    lines.push(format!("    {:?} -> {};", parent, node));
+                }
+                lines.push(format!("    {} -> {:?};", node, id));
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
     }
 
-    fn meet_mut(&mut self, other: Self) -> bool {
-        let old_len = self.derivations.len();
-        self.derivations.extend(other.derivations);
-        self.derivations.len() != old_len
+    // Proof of a fact as nested {"fact", "rule", "premises"} dicts down to the axioms, using the
+    // minimum-depth derivation of every step. Parents are strictly shallower, so provenance
+    // cycles are never followed and facts only derivable through one have no tree (None).
+    fn proof_tree(&self, py: Python<'_>, fact_id: String) -> PyResult<Option<PyObject>> {
+        let depths = self.proof_depths();
+        let id = canonical_fact_id(&fact_id);
+        if !depths.contains_key(&id) {
+            return Ok(None);
+        }
+        Self::proof_tree_node(py, &depths, &id).map(Some)
     }
 
-    fn join_mut(&mut self, other: Self) -> bool {
-        self.meet_mut(other)
+    // Shortest proof of a fact: the steps of its minimum-depth proof as {"fact", "rule",
+    // "premises", "depth"} dicts, parents first (axioms have depth 0). None if not derived.
+    fn shortest_proof(&self, py: Python<'_>, fact_id: String) -> PyResult<Option<PyObject>> {
+        let depths = self.proof_depths();
+        let id = canonical_fact_id(&fact_id);
+        let Some(steps) = self.proof_steps(&depths, &id) else {
+            return Ok(None);
+        };
+
+        let chain = PyList::empty_bound(py);
+        for f in steps {
+            let (depth, derivation) = depths[f];
+            let step = PyDict::new_bound(py);
+            step.set_item("fact", f)?;
+            step.set_item("rule", &derivation.rule)?;
+            step.set_item("premises", derivation.parents.iter().collect::<Vec<_>>())?;
+            step.set_item("depth", depth)?;
+            chain.append(step)?;
+        }
+        Ok(Some(chain.into_any().unbind()))
     }
-}
 
-fn fact_id(pred_type: &str, args: impl IntoIterator<Item = impl AsRef<str>>) -> String {
-    let args_str: Vec<String> = args.into_iter()
-        .map(|s| s.as_ref().to_string())
-        .collect();
-    format!("{}({})", pred_type, args_str.join(","))
-}
+    // Ids of input facts naming a point that was never added with `add_point`; rules that
+    // read point coordinates never fire for such facts
+    fn validate(&self) -> Vec<String> {
+        let names: HashSet<&str> = self.points.iter().map(|(_, _, name)| name.as_str()).collect();
+        self.input_facts()
+            .into_iter()
+            .filter(|(_, args, point_args)| args[..*point_args].iter().any(|a| !names.contains(a.as_str())))
+            .map(|(pred, args, _)| fact_id(pred, args))
+            .collect()
+    }
 
-#[pyclass]
-struct DeductiveDatabase {
-    // Input facts
-    points: Vec<(i64, i64, String)>,
-    col_facts: Vec<(String, String, String)>,
-    para_facts: Vec<(String, String, String, String)>,
-    perp_facts: Vec<(String, String, String, String)>,
-    cong_facts: Vec<(String, String, String, String)>,
-    eqangle_facts: Vec<(String, String, String, String, String, String)>,
-    cyclic_facts: Vec<(String, String, String, String)>,
-    sameclock_facts: Vec<(String, String, String, String, String, String)>,
-    midp_facts: Vec<(String, String, String)>,
-    contri1_facts: Vec<(String, String, String, String, String, String)>,
-    contri2_facts: Vec<(String, String, String, String, String, String)>,
-    simtri1_facts: Vec<(String, String, String, String, String, String)>,
-    simtri2_facts: Vec<(String, String, String, String, String, String)>,
-    eqratio_facts: Vec<(String, String, String, String, String, String, String, String)>,
-    aconst_facts: Vec<(String, String, String, i32, i32)>,
+    // Goal queries: whether a fact was derived, up to the symmetries of its predicate
+    fn has_col(&self, a: String, b: String, c: String) -> bool {
+        self.find_derived("col", vec![a, b, c]).is_some()
+    }
 
-    // Derived results with provenance
-    derived_col: Vec<(String, String, String, Provenance)>,
-    derived_para: Vec<(String, String, String, String, Provenance)>,
-    derived_perp: Vec<(String, String, String, String, Provenance)>,
-    derived_cong: Vec<(String, String, String, String, Provenance)>,
-    derived_eqangle: Vec<(String, String, String, String, String, String, Provenance)>,
-    derived_cyclic: Vec<(String, String, String, String, Provenance)>,
-    derived_sameclock: Vec<(String, String, String, String, String, String, Provenance)>,
-    derived_midp: Vec<(String, String, String, Provenance)>,
-    derived_contri1: Vec<(String, String, String, String, String, String, Provenance)>,
-    derived_contri2: Vec<(String, String, String, String, String, String, Provenance)>,
-    derived_simtri1: Vec<(String, String, String, String, String, String, Provenance)>,
-    derived_simtri2: Vec<(String, String, String, String, String, String, Provenance)>,
-    derived_eqratio: Vec<(String, String, String, String, String, String, String, String, Provenance)>,
-    derived_aconst: Vec<(String, String, String, i32, i32, Provenance)>,
-}
+    fn has_para(&self, a: String, b: String, c: String, d: String) -> bool {
+        self.find_derived("para", vec![a, b, c, d]).is_some()
+    }
 
-#[pymethods]
-impl DeductiveDatabase {
-    #[new]
-    fn new() -> Self {
-        DeductiveDatabase {
-            points: Vec::new(),
-            col_facts: Vec::new(),
-            para_facts: Vec::new(),
-            perp_facts: Vec::new(),
-            cong_facts: Vec::new(),
-            eqangle_facts: Vec::new(),
-            cyclic_facts: Vec::new(),
-            sameclock_facts: Vec::new(),
-            midp_facts: Vec::new(),
-            contri1_facts: Vec::new(),
-            contri2_facts: Vec::new(),
-            simtri1_facts: Vec::new(),
-            simtri2_facts: Vec::new(),
-            eqratio_facts: Vec::new(),
-            aconst_facts: Vec::new(),
-
-            derived_col: Vec::new(),
-            derived_para: Vec::new(),
-            derived_perp: Vec::new(),
-            derived_cong: Vec::new(),
-            derived_eqangle: Vec::new(),
-            derived_cyclic: Vec::new(),
-            derived_sameclock: Vec::new(),
-            derived_midp: Vec::new(),
-            derived_contri1: Vec::new(),
-            derived_contri2: Vec::new(),
-            derived_simtri1: Vec::new(),
-            derived_simtri2: Vec::new(),
-            derived_eqratio: Vec::new(),
-            derived_aconst: Vec::new(),
-        }
-    }
-
-    fn add_point(&mut self, x: i64, y: i64, name: String) {
-        if self.points.iter().any(|(_, _, n)| n == &name) {
-            return;
-        }
-        self.points.push((x, y, name.clone()));
+    fn has_perp(&self, a: String, b: String, c: String, d: String) -> bool {
+        self.find_derived("perp", vec![a, b, c, d]).is_some()
     }
 
-    fn add_col(&mut self, a: String, b: String, c: String) {
-        self.col_facts.push((a, b, c));
+    fn has_cong(&self, a: String, b: String, c: String, d: String) -> bool {
+        self.find_derived("cong", vec![a, b, c, d]).is_some()
     }
 
-    fn add_para(&mut self, a: String, b: String, c: String, d: String) {
-        self.para_facts.push((a, b, c, d));
+    fn has_eqangle(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> bool {
+        self.find_derived("eqangle", vec![a, b, c, d, e, f]).is_some()
     }
 
-    fn add_perp(&mut self, a: String, b: String, c: String, d: String) {
-        self.perp_facts.push((a, b, c, d));
+    fn has_cyclic(&self, a: String, b: String, c: String, d: String) -> bool {
+        self.find_derived("cyclic", vec![a, b, c, d]).is_some()
     }
 
-    fn add_cong(&mut self, a: String, b: String, c: String, d: String) {
-        self.cong_facts.push((a, b, c, d));
+    fn has_midp(&self, m: String, a: String, b: String) -> bool {
+        self.find_derived("midp", vec![m, a, b]).is_some()
     }
 
-    fn add_eqangle(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
-        self.eqangle_facts.push((a, b, c, d, e, f));
+    fn has_contri1(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> bool {
+        self.find_derived("contri1", vec![a, b, c, d, e, f]).is_some()
     }
 
-    fn add_cyclic(&mut self, a: String, b: String, c: String, d: String) {
-        self.cyclic_facts.push((a, b, c, d));
+    fn has_contri2(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> bool {
+        self.find_derived("contri2", vec![a, b, c, d, e, f]).is_some()
     }
 
-    fn add_sameclock(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
-        self.sameclock_facts.push((a, b, c, d, e, f));
+    fn has_simtri1(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> bool {
+        self.find_derived("simtri1", vec![a, b, c, d, e, f]).is_some()
     }
 
-    fn add_midp(&mut self, a: String, b: String, c: String) {
-        self.midp_facts.push((a, b, c));
+    fn has_simtri2(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> bool {
+        self.find_derived("simtri2", vec![a, b, c, d, e, f]).is_some()
     }
 
-    fn add_contri1(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
-        self.contri1_facts.push((a, b, c, d, e, f));
+    #[allow(clippy::too_many_arguments)]
+    fn has_eqratio(&self, a: String, b: String, c: String, d: String, e: String, f: String, g: String, h: String) -> bool {
+        self.find_derived("eqratio", vec![a, b, c, d, e, f, g, h]).is_some()
     }
 
-    fn add_contri2(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
-        self.contri2_facts.push((a, b, c, d, e, f));
+    // Constants are matched as given, without reducing the fraction
+    fn has_aconst(&self, a: String, b: String, c: String, m: i32, n: i32) -> bool {
+        self.find_derived("aconst", vec![a, b, c, m.to_string(), n.to_string()]).is_some()
     }
 
-    fn add_simtri1(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
-        self.simtri1_facts.push((a, b, c, d, e, f));
+    fn has_lconst(&self, a: String, b: String, m: i32, n: i32) -> bool {
+        self.find_derived("lconst", vec![a, b, m.to_string(), n.to_string()]).is_some()
     }
 
-    fn add_simtri2(&mut self, a: String, b: String, c: String, d: String, e: String, f: String) {
-        self.simtri2_facts.push((a, b, c, d, e, f));
+    fn has_rconst(&self, a: String, b: String, c: String, d: String, m: i32, n: i32) -> bool {
+        self.find_derived("rconst", vec![a, b, c, d, m.to_string(), n.to_string()]).is_some()
     }
 
-    fn add_eqratio(&mut self, a: String, b: String, c: String, d: String, e: String, f: String, g: String, h: String) {
-        self.eqratio_facts.push((a, b, c, d, e, f, g, h));
+    fn has_eq(&self, a: String, b: String) -> bool {
+        self.find_derived("eq", vec![a, b]).is_some()
     }
 
-    fn add_aconst(&mut self, a: String, b: String, c: String, m: i32, n: i32) {
-        self.aconst_facts.push((a, b, c, m, n));
+    fn has_tangent(&self, a: String, b: String, o: String, p: String) -> bool {
+        self.find_derived("tangent", vec![a, b, o, p]).is_some()
     }
 
-    fn run(&mut self) {
-        let points = self.points.clone();
-
-        let col_facts = self.col_facts.clone();
-        let para_facts = self.para_facts.clone();
-        let perp_facts = self.perp_facts.clone();
-        let cong_facts = self.cong_facts.clone();
-        let eqangle_facts = self.eqangle_facts.clone();
-        let cyclic_facts = self.cyclic_facts.clone();
-        let sameclock_facts = self.sameclock_facts.clone();
-        let midp_facts = self.midp_facts.clone();
-        let contri1_facts = self.contri1_facts.clone();
-        let contri2_facts = self.contri2_facts.clone();
-        let simtri1_facts = self.simtri1_facts.clone();
-        let simtri2_facts = self.simtri2_facts.clone();
-        let eqratio_facts = self.eqratio_facts.clone();
-        let aconst_facts = self.aconst_facts.clone();
-
-        ascent! {
-            struct AscentProgram;
-
-            relation point(i64, i64, String);
-
-            lattice col(String, String, String, Provenance);
-            lattice para(String, String, String, String, Provenance);
-            lattice perp(String, String, String, String, Provenance);
-            lattice cong(String, String, String, String, Provenance);
-            lattice eqangle(String, String, String, String, String, String, Provenance);
-            lattice cyclic(String, String, String, String, Provenance);
-            lattice sameclock(String, String, String, String, String, String, Provenance);
-            lattice eqratio(String, String, String, String, String, String, String, String, Provenance);
-            lattice midp(String, String, String, Provenance);
-            lattice contri1(String, String, String, String, String, String, Provenance);
-            lattice contri2(String, String, String, String, String, String, Provenance);
-            lattice simtri1(String, String, String, String, String, String, Provenance);
-            lattice simtri2(String, String, String, String, String, String, Provenance);
-            lattice aconst(String, String, String, i32, i32, Provenance);
-
-            // ----------------------------------------------------------------
-            // Relation Properties (Symmetries)
-            // ----------------------------------------------------------------
-
-            col(c, b, a, Provenance::from("sym", vec![fact_id("col", [a, b, c])]))
-                <-- col(a, b, c, ?_prov);
-            col(a, c, b, Provenance::from("sym", vec![fact_id("col", [a, b, c])]))
-                <-- col(a, b, c, ?_prov);
-
-            para(c, d, a, b, Provenance::from("sym", vec![fact_id("para", [a, b, c, d])]))
-                <-- para(a, b, c, d, ?_prov);
-            para(b, a, c, d, Provenance::from("sym", vec![fact_id("para", [a, b, c, d])]))
-                <-- para(a, b, c, d, ?_prov);
-            para(a, b, d, c, Provenance::from("sym", vec![fact_id("para", [a, b, c, d])]))
-                <-- para(a, b, c, d, ?_prov);
-
-            perp(c, d, a, b, Provenance::from("sym", vec![fact_id("perp", [a, b, c, d])]))
-                <-- perp(a, b, c, d, ?_prov);
-            perp(b, a, c, d, Provenance::from("sym", vec![fact_id("perp", [a, b, c, d])]))
-                <-- perp(a, b, c, d, ?_prov);
-            perp(a, b, d, c, Provenance::from("sym", vec![fact_id("perp", [a, b, c, d])]))
-                <-- perp(a, b, c, d, ?_prov);
-
-            cong(c, d, a, b, Provenance::from("sym", vec![fact_id("cong", [a, b, c, d])]))
-                <-- cong(a, b, c, d, ?_prov);
-            cong(b, a, c, d, Provenance::from("sym", vec![fact_id("cong", [a, b, c, d])]))
-                <-- cong(a, b, c, d, ?_prov);
-            cong(a, b, d, c, Provenance::from("sym", vec![fact_id("cong", [a, b, c, d])]))
-                <-- cong(a, b, c, d, ?_prov);
-
-            eqangle(d, e, f, a, b, c, Provenance::from("sym", vec![fact_id("eqangle", [a, b, c, d, e, f])]))
-                <-- eqangle(a, b, c, d, e, f, ?_prov);
-            eqangle(c, b, a, f, e, d, Provenance::from("sym", vec![fact_id("eqangle", [a, b, c, d, e, f])]))
-                <-- eqangle(a, b, c, d, e, f, ?_prov);
-
-            cyclic(b, c, d, a, Provenance::from("sym", vec![fact_id("cyclic", [a, b, c, d])]))
-                <-- cyclic(a, b, c, d, ?_prov);
-            cyclic(a, c, b, d, Provenance::from("sym", vec![fact_id("cyclic", [a, b, c, d])]))
-                <-- cyclic(a, b, c, d, ?_prov);
-
-            sameclock(d, e, f, a, b, c, Provenance::from("sym", vec![fact_id("sameclock", [a, b, c, d, e, f])]))
-                <-- sameclock(a, b, c, d, e, f, ?_prov);
-            sameclock(a, b, c, f, d, e, Provenance::from("sym", vec![fact_id("sameclock", [a, b, c, d, e, f])]))
-                <-- sameclock(a, b, c, d, e, f, ?_prov);
-            sameclock(c, b, a, f, e, d, Provenance::from("sym", vec![fact_id("sameclock", [a, b, c, d, e, f])]))
-                <-- sameclock(a, b, c, d, e, f, ?_prov);
-
-            eqratio(b, a, c, d, e, f, g, h, Provenance::from("sym", vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
-                <-- eqratio(a, b, c, d, e, f, g, h, ?_prov);
-            eqratio(e, f, g, h, a, b, c, d, Provenance::from("sym", vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
-                <-- eqratio(a, b, c, d, e, f, g, h, ?_prov);
-            eqratio(c, d, a, b, g, h, e, f, Provenance::from("sym", vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
-                <-- eqratio(a, b, c, d, e, f, g, h, ?_prov);
-            eqratio(a, b, e, f, c, d, g, h, Provenance::from("sym", vec![fact_id("eqratio", [a, b, c, d, e, f, g, h])]))
-                <-- eqratio(a, b, c, d, e, f, g, h, ?_prov);
-
-            // ----------------------------------------------------------------
-            // Trivial Statements
-            // ----------------------------------------------------------------
-
-            cong(a, b, a, b, Provenance::from("rfl", vec![])) <--
-                point(_, _, a), point(_, _, b),
-                if a != b;
-
-            para(a, b, a, b, Provenance::from("rfl", vec![])) <--
-                point(_, _, a), point(_, _, b),
-                if a != b;
-
-            eqangle(a, b, c, a, b, c, Provenance::from("rfl", vec![])) <--
-                point(_, _, a), point(_, _, b), point(_, _, c),
-                if a != b && a != c && b != c;
-
-            // ----------------------------------------------------------------
-            // Deductive Rules
-            // ----------------------------------------------------------------
-
-            // Right Angle Equal
-            eqangle(c, b, a, b, e, a, Provenance::from("right_angle_eq", vec![
-                fact_id("perp", [a, b, b_prime, c]),
-                fact_id("perp", [a, e, e_prime, b])
-            ])) <--
-                perp(a, b, b_prime, c, ?_prov1),
-                perp(a, e, e_prime, b, ?_prov2),
-                if b == b_prime && e == e_prime &&
-                   a != b && a != c && a != e &&
-                   b != c && b != e &&
-                   c != e;
-
-            // AA Similarity
-            simtri1(a, b, c, d, e, f, Provenance::from("aa_sim", vec![
-                fact_id("eqangle", [b, a, c, e, d, f]),
-                fact_id("eqangle", [b, c, a, e, f, d])
-            ])) <--
-                eqangle(b, a, c, e, d, f, ?_prov1),
-                eqangle(b, c, a, e, f, d, ?_prov2),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)]
-                );
-
-            simtri2(a, b, c, d, e, f, Provenance::from("aa_sim", vec![
-                fact_id("eqangle", [b, a, c, f, d, e]),
-                fact_id("eqangle", [b, c, a, d, f, e])
-            ])) <--
-                eqangle(b, a, c, f, d, e, ?_prov1),
-                eqangle(b, c, a, d, f, e, ?_prov2),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)]
-                );
-
-            // ASA Congruence
-            contri1(a, b, c, d, e, f, Provenance::from("asa_cong", vec![
-                fact_id("eqangle", [b, a, c, e, d, f]),
-                fact_id("eqangle", [c, b, a, f, e, d]),
-                fact_id("cong", [a, b, d, e])
-            ])) <--
-                eqangle(b, a, c, e, d, f, ?_prov1),
-                eqangle(c, b, a, f, e, d, ?_prov2),
-                cong(a, b, d, e, ?_prov3),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)]
-                );
-
-            contri2(a, b, c, d, e, f, Provenance::from("asa_cong", vec![
-                fact_id("eqangle", [b, a, c, f, d, e]),
-                fact_id("eqangle", [c, b, a, d, e, f]),
-                fact_id("cong", [a, b, d, e])
-            ])) <--
-                eqangle(b, a, c, f, d, e, ?_prov1),
-                eqangle(c, b, a, d, e, f, ?_prov2),
-                cong(a, b, d, e, ?_prov3),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)]
-                );
-
-            // SAS Congruence
-            contri1(a, b, c, d, e, f, Provenance::from("sas_cong", vec![
-                fact_id("eqangle", [b, a, c, e, d, f]),
-                fact_id("cong", [a, c, d, f]),
-                fact_id("cong", [a, b, d, e])
-            ])) <--
-                eqangle(b, a, c, e, d, f, ?_prov1),
-                cong(a, c, d, f, ?_prov2),
-                cong(a, b, d, e, ?_prov3),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)]
-                );
-
-            contri2(a, b, c, d, e, f, Provenance::from("sas_cong", vec![
-                fact_id("eqangle", [b, a, c, f, d, e]),
-                fact_id("cong", [a, c, d, f]),
-                fact_id("cong", [a, b, d, e])
-            ])) <--
-                eqangle(b, a, c, f, d, e, ?_prov1),
-                cong(a, c, d, f, ?_prov2),
-                cong(a, b, d, e, ?_prov3),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)]
-                );
-
-            // SSS Congruence
-            contri1(a, b, c, d, e, f, Provenance::from("sss_cong", vec![
-                fact_id("cong", [a, c, d, f]),
-                fact_id("cong", [a, b, d, e]),
-                fact_id("cong", [c, b, f, e])
-            ])) <--
-                cong(a, c, d, f, ?_prov1),
-                cong(a, b, d, e, ?_prov2),
-                cong(c, b, f, e, ?_prov3),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)]
-                );
-
-            contri2(a, b, c, d, e, f, Provenance::from("sss_cong", vec![
-                fact_id("cong", [a, c, d, f]),
-                fact_id("cong", [a, b, d, e]),
-                fact_id("cong", [c, b, f, e])
-            ])) <--
-                cong(a, c, d, f, ?_prov1),
-                cong(a, b, d, e, ?_prov2),
-                cong(c, b, f, e, ?_prov3),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)]
-                );
-
-            // Right SSA Congruence
-            contri1(a, b, c, d, e, f, Provenance::from("ssa_right_cong", vec![
-                fact_id("perp", [a, b, a_prime, c]),
-                fact_id("perp", [d, e, d_prime, f]),
-                fact_id("cong", [a, b, d, e]),
-                fact_id("cong", [b, c, e, f])
-            ])) <--
-                perp(a, b, a_prime, c, ?_prov1),
-                perp(d, e, d_prime, f, ?_prov2),
-                cong(a, b, d, e, ?_prov3),
-                cong(b, c, e, f, ?_prov4),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*dx, *dy), (*ex, *ey), (*fx, *fy)]
-            ) && a == a_prime && d == d_prime;
-
-            contri2(a, b, c, d, e, f, Provenance::from("ssa_right_cong", vec![
-                fact_id("perp", [a, b, a_prime, c]),
-                fact_id("perp", [d, e, d_prime, f]),
-                fact_id("cong", [a, b, d, e]),
-                fact_id("cong", [b, c, e, f])
-            ])) <--
-                perp(a, b, a_prime, c, ?_prov1),
-                perp(d, e, d_prime, f, ?_prov2),
-                cong(a, b, d, e, ?_prov3),
-                cong(b, c, e, f, ?_prov4),
-                point(ax, ay, a), point(bx, by, b), point(cx, cy, c),
-                point(dx, dy, d), point(ex, ey, e), point(fx, fy, f),
-                if same_orientation(
-                    vec![(*ax, *ay), (*bx, *by), (*cx, *cy)],
-                    vec![(*fx, *fy), (*ex, *ey), (*dx, *dy)]
-                ) && a == a_prime && d == d_prime;
-
-            // Inscribed Angle Theorem
-            eqangle(a, b, c, c, b, d, Provenance::from("inscribed_angle_thm", vec![
-                fact_id("cong", [o, a, o_prime, b]),
-                fact_id("cong", [o, c, o_prime, b]),
-                fact_id("cong", [o, c, o_prime, a]),
-                fact_id("perp", [o, b, b_prime, d]),
-                fact_id("eqangle", [a, o, c, c_prime, o, b])
-            ])) <--
-                cong(o, a, o_prime, b, ?_prov1),
-                cong(o, c, o_prime, b, ?_prov2),
-                cong(o, c, o_prime, a, ?_prov3),
-                perp(o, b, b_prime, d, ?_prov4),
-                eqangle(a, o, c, c_prime, o, b, ?_prov5),
-                if o == o_prime && b == b_prime && c == c_prime &&
-                   a != b && a != c && a != d &&
-                   b != c && b != d &&
-                   c != d;
-
-            // Thales's theorem
-            perp(b, r, r, d, Provenance::from("thales_thm", vec![
-                fact_id("cyclic", [b, r, y, d]),
-                fact_id("cong", [b, o, r, o_prime]),
-                fact_id("cong", [r, o, d, o_prime]),
-                fact_id("col", [b, o, d])
-            ])) <--
-                cyclic(b, r, y, d, ?_prov1),
-                cong(b, o, r, o_prime, ?_prov2),
-                cong(r, o, d, o_prime, ?_prov3),
-                col(b, o, d, ?_prov4),
-                if o == o_prime &&
-                   b != r && b != y && b != d &&
-                   r != y && r != d &&
-                   y != d;
-
-            // Eqratio is Additive
-            eqratio(a, m, b, n, m, c, n, d, Provenance::from("eqratio_additive", vec![
-                fact_id("eqratio", [a, c, m, c, b, d, n, d]),
-                fact_id("col", [a, m, c]),
-                fact_id("col", [b, n, d])
-            ])) <--
-                eqratio(a, c, m, c_prime, b, d, n, d_prime, ?_prov1),
-                col(a, m, c, ?_prov2),
-                col(b, n, d, ?_prov3),
-                if a != m && a != c &&
-                   m != c &&
-                   b != n && b != d &&
-                   n != d &&
-                   c == c_prime && d == d_prime;
-
-            eqratio(a, c, m, c, b, d, n, d, Provenance::from("eqratio_additive", vec![
-                fact_id("eqratio", [a, m, b, n, m, c, n, d]),
-                fact_id("col", [a, m, c]),
-                fact_id("col", [b, n, d])
-            ])) <--
-                eqratio(a, m, b, n, m_prime, c, n_prime, d, ?_prov1),
-                col(a, m, c, ?_prov2),
-                col(b, n, d, ?_prov3),
-                if a != m && a != c &&
-                   m != c &&
-                   b != n && b != d &&
-                   n != d &&
-                   m == m_prime && n == n_prime;
-        }
+    // Derivations of a goal fact, matched up to the symmetries of its predicate; None if it
+    // was not derived
+    fn explain_col(&self, a: String, b: String, c: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("col", vec![a, b, c]).map(Provenance::to_list)
+    }
 
-        let mut prog = AscentProgram::default();
+    fn explain_para(&self, a: String, b: String, c: String, d: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("para", vec![a, b, c, d]).map(Provenance::to_list)
+    }
 
-        // Initialize input relations with axiom provenance
-        prog.point = points;
-        prog.col = col_facts.into_iter().map(|(a, b, c)| (a, b, c, Provenance::axiom())).collect();
-        prog.para = para_facts.into_iter().map(|(a, b, c, d)| (a, b, c, d, Provenance::axiom())).collect();
-        prog.perp = perp_facts.into_iter().map(|(a, b, c, d)| (a, b, c, d, Provenance::axiom())).collect();
-        prog.cong = cong_facts.into_iter().map(|(a, b, c, d)| (a, b, c, d, Provenance::axiom())).collect();
-        prog.eqangle = eqangle_facts.into_iter().map(|(a, b, c, d, e, f)| (a, b, c, d, e, f, Provenance::axiom())).collect();
-        prog.cyclic = cyclic_facts.into_iter().map(|(a, b, c, d)| (a, b, c, d, Provenance::axiom())).collect();
-        prog.sameclock = sameclock_facts.into_iter().map(|(a, b, c, d, e, f)| (a, b, c, d, e, f, Provenance::axiom())).collect();
-        prog.midp = midp_facts.into_iter().map(|(a, b, c)| (a, b, c, Provenance::axiom())).collect();
-        prog.contri1 = contri1_facts.into_iter().map(|(a, b, c, d, e, f)| (a, b, c, d, e, f, Provenance::axiom())).collect();
-        prog.contri2 = contri2_facts.into_iter().map(|(a, b, c, d, e, f)| (a, b, c, d, e, f, Provenance::axiom())).collect();
-        prog.simtri1 = simtri1_facts.into_iter().map(|(a, b, c, d, e, f)| (a, b, c, d, e, f, Provenance::axiom())).collect();
-        prog.simtri2 = simtri2_facts.into_iter().map(|(a, b, c, d, e, f)| (a, b, c, d, e, f, Provenance::axiom())).collect();
-        prog.eqratio = eqratio_facts.into_iter().map(|(a, b, c, d, e, f, g, h)| (a, b, c, d, e, f, g, h, Provenance::axiom())).collect();
-        prog.aconst = aconst_facts.into_iter().map(|(a, b, c, m, n)| (a, b, c, m, n, Provenance::axiom())).collect();
-
-        prog.run();
+    fn explain_perp(&self, a: String, b: String, c: String, d: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("perp", vec![a, b, c, d]).map(Provenance::to_list)
+    }
 
-        // Extract derived results
-        self.derived_col = prog.col;
-        self.derived_para = prog.para;
-        self.derived_perp = prog.perp;
-        self.derived_cong = prog.cong;
-        self.derived_eqangle = prog.eqangle;
-        self.derived_cyclic = prog.cyclic;
-        self.derived_sameclock = prog.sameclock;
-        self.derived_midp = prog.midp;
-        self.derived_contri1 = prog.contri1;
-        self.derived_contri2 = prog.contri2;
-        self.derived_simtri1 = prog.simtri1;
-        self.derived_simtri2 = prog.simtri2;
-        self.derived_eqratio = prog.eqratio;
-        self.derived_aconst = prog.aconst;
-    }
-
-    // Output methods
-    fn get_points(&self) -> Vec<(i64, i64, String)> {
+    fn explain_cong(&self, a: String, b: String, c: String, d: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("cong", vec![a, b, c, d]).map(Provenance::to_list)
+    }
+
+    fn explain_eqangle(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("eqangle", vec![a, b, c, d, e, f]).map(Provenance::to_list)
+    }
+
+    fn explain_cyclic(&self, a: String, b: String, c: String, d: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("cyclic", vec![a, b, c, d]).map(Provenance::to_list)
+    }
+
+    fn explain_midp(&self, m: String, a: String, b: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("midp", vec![m, a, b]).map(Provenance::to_list)
+    }
+
+    fn explain_contri1(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("contri1", vec![a, b, c, d, e, f]).map(Provenance::to_list)
+    }
+
+    fn explain_contri2(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("contri2", vec![a, b, c, d, e, f]).map(Provenance::to_list)
+    }
+
+    fn explain_simtri1(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("simtri1", vec![a, b, c, d, e, f]).map(Provenance::to_list)
+    }
+
+    fn explain_simtri2(&self, a: String, b: String, c: String, d: String, e: String, f: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("simtri2", vec![a, b, c, d, e, f]).map(Provenance::to_list)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn explain_eqratio(&self, a: String, b: String, c: String, d: String, e: String, f: String, g: String, h: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("eqratio", vec![a, b, c, d, e, f, g, h]).map(Provenance::to_list)
+    }
+
+    fn explain_aconst(&self, a: String, b: String, c: String, m: i32, n: i32) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("aconst", vec![a, b, c, m.to_string(), n.to_string()]).map(Provenance::to_list)
+    }
+
+    fn explain_lconst(&self, a: String, b: String, m: i32, n: i32) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("lconst", vec![a, b, m.to_string(), n.to_string()]).map(Provenance::to_list)
+    }
+
+    fn explain_rconst(&self, a: String, b: String, c: String, d: String, m: i32, n: i32) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("rconst", vec![a, b, c, d, m.to_string(), n.to_string()]).map(Provenance::to_list)
+    }
+
+    fn explain_eq(&self, a: String, b: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("eq", vec![a, b]).map(Provenance::to_list)
+    }
+
+    fn explain_tangent(&self, a: String, b: String, o: String, p: String) -> Option<Vec<(String, Vec<String>)>> {
+        self.find_derived("tangent", vec![a, b, o, p]).map(Provenance::to_list)
+    }
+
+    // Output methods; derived facts are returned sorted so the output is stable across runs
+    fn get_points(&self) -> Vec<(Coord, Coord, String)> {
         self.points.clone()
     }
 
     fn get_col(&self) -> Vec<(String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_col.iter()
+        sorted(self.derived_col.iter()
             .map(|(a, b, c, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_para(&self) -> Vec<(String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_para.iter()
+        sorted(self.derived_para.iter()
             .map(|(a, b, c, d, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_perp(&self) -> Vec<(String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_perp.iter()
+        sorted(self.derived_perp.iter()
             .map(|(a, b, c, d, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_cong(&self) -> Vec<(String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_cong.iter()
+        sorted(self.derived_cong.iter()
             .map(|(a, b, c, d, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_eqangle(&self) -> Vec<(String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_eqangle.iter()
+        sorted(self.derived_eqangle.iter()
             .map(|(a, b, c, d, e, f, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_cyclic(&self) -> Vec<(String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_cyclic.iter()
+        sorted(self.derived_cyclic.iter()
             .map(|(a, b, c, d, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_sameclock(&self) -> Vec<(String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_sameclock.iter()
+        sorted(self.derived_sameclock.iter()
             .map(|(a, b, c, d, e, f, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_midp(&self) -> Vec<(String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_midp.iter()
+        sorted(self.derived_midp.iter()
             .map(|(a, b, c, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_contri1(&self) -> Vec<(String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_contri1.iter()
+        sorted(self.derived_contri1.iter()
             .map(|(a, b, c, d, e, f, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_contri2(&self) -> Vec<(String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_contri2.iter()
+        sorted(self.derived_contri2.iter()
             .map(|(a, b, c, d, e, f, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_simtri1(&self) -> Vec<(String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_simtri1.iter()
+        sorted(self.derived_simtri1.iter()
             .map(|(a, b, c, d, e, f, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_simtri2(&self) -> Vec<(String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_simtri2.iter()
+        sorted(self.derived_simtri2.iter()
             .map(|(a, b, c, d, e, f, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_eqratio(&self) -> Vec<(String, String, String, String, String, String, String, String, Vec<(String, Vec<String>)>)> {
-        self.derived_eqratio.iter()
+        sorted(self.derived_eqratio.iter()
             .map(|(a, b, c, d, e, f, g, h, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), g.clone(), h.clone(), derivations)
             })
-            .collect()
+            .collect())
     }
 
     fn get_aconst(&self) -> Vec<(String, String, String, i32, i32, Vec<(String, Vec<String>)>)> {
-        self.derived_aconst.iter()
+        sorted(self.derived_aconst.iter()
             .map(|(a, b, c, m, n, prov)| {
                 let derivations = prov.derivations.iter()
                     .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
                     .collect();
                 (a.clone(), b.clone(), c.clone(), *m, *n, derivations)
             })
-            .collect()
+            .collect())
+    }
+
+    fn get_lconst(&self) -> Vec<(String, String, i32, i32, Vec<(String, Vec<String>)>)> {
+        sorted(self.derived_lconst.iter()
+            .map(|(a, b, m, n, prov)| {
+                let derivations = prov.derivations.iter()
+                    .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
+                    .collect();
+                (a.clone(), b.clone(), *m, *n, derivations)
+            })
+            .collect())
+    }
+
+    fn get_rconst(&self) -> Vec<(String, String, String, String, i32, i32, Vec<(String, Vec<String>)>)> {
+        sorted(self.derived_rconst.iter()
+            .map(|(a, b, c, d, m, n, prov)| {
+                let derivations = prov.derivations.iter()
+                    .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
+                    .collect();
+                (a.clone(), b.clone(), c.clone(), d.clone(), *m, *n, derivations)
+            })
+            .collect())
+    }
+
+    // Pairs of point names the rules forced to be the same point, e.g. two declared
+    // midpoints of one segment
+    fn get_eq(&self) -> Vec<(String, String, Vec<(String, Vec<String>)>)> {
+        sorted(self.derived_eq.iter()
+            .map(|(a, b, prov)| {
+                let derivations = prov.derivations.iter()
+                    .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
+                    .collect();
+                (a.clone(), b.clone(), derivations)
+            })
+            .collect())
+    }
+
+    // Every derived fact as (predicate, args, derivations) in one list, with numeric args
+    // stringified
+    fn get_all_facts(&self) -> Vec<(String, Vec<String>, Vec<(String, Vec<String>)>)> {
+        sorted(self.derived_facts().into_iter()
+            .map(|(pred, args, prov)| (pred.to_string(), args, prov.to_list()))
+            .collect())
+    }
+
+    // Derived facts of one predicate as `Fact` objects, an alternative to unpacking the
+    // tuples of the get_* methods; empty for an unknown predicate
+    fn get_facts(&self, predicate: &str) -> Vec<Fact> {
+        let mut facts: Vec<Fact> = self.derived_facts().into_iter()
+            .filter(|(pred, _, _)| *pred == predicate)
+            .map(|(pred, args, prov)| Fact {
+                predicate: pred.to_string(),
+                args,
+                derivations: prov.to_list(),
+            })
+            .collect();
+        facts.sort_by(|f, g| (&f.args, &f.derivations).cmp(&(&g.args, &g.derivations)));
+        facts
+    }
+
+    fn get_tangent(&self) -> Vec<(String, String, String, String, Vec<(String, Vec<String>)>)> {
+        sorted(self.derived_tangent.iter()
+            .map(|(a, b, o, p, prov)| {
+                let derivations = prov.derivations.iter()
+                    .map(|d| (d.rule.clone(), d.parents.iter().cloned().collect()))
+                    .collect();
+                (a.clone(), b.clone(), o.clone(), p.clone(), derivations)
+            })
+            .collect())
+    }
+}
+
+impl DeductiveDatabase {
+    // The database with `input_ids` filled in from its input facts, as after loading
+    fn with_input_ids(mut self) -> Self {
+        self.input_ids = self.input_facts().into_iter().map(|(pred, args, _)| fact_id(pred, args)).collect();
+        self
+    }
+
+    fn provenance_settings(&self, track: bool) -> ProvenanceSettings {
+        ProvenanceSettings { track, max_derivations: self.max_derivations_per_fact }
+    }
+
+    // Seed a fresh program with the points and input facts and saturate it, stopping at
+    // `deadline` if given, recording derivations if `track`
+    fn run_until(&mut self, deadline: Option<Instant>, track: bool) {
+        let enabled_rules: Vec<(String,)> = self.enabled_rules.iter().cloned().map(|r| (r,)).collect();
+        let disabled_rules: Vec<(String,)> = self.disabled_rules.iter().cloned().map(|r| (r,)).collect();
+        let settings = self.provenance_settings(track);
+
+        let mut prog = AscentProgram::default();
+        prog.epsilon = vec![(Coord::Float(self.epsilon.unwrap_or(0.0)),)];
+        prog.rule_enabled = enabled_rules;
+        prog.rule_disabled = disabled_rules;
+        prog.provenance_settings = vec![(settings,)];
+        self.seed_inputs(&mut prog, settings, &DeductiveDatabase::default());
+
+        self.saturate(prog, self.without_derived(), deadline);
+    }
+
+    // Seed a program with the points and input facts after the first ones `seen` by it, as
+    // axioms, along with the trivial and coordinate facts. A fresh program has seen none.
+    fn seed_inputs(&self, prog: &mut AscentProgram, settings: ProvenanceSettings, seen: &DeductiveDatabase) {
+        prog.point.extend(self.points[seen.points.len()..].iter().cloned());
+        seed_trivial(prog, settings, &self.points, seen.points.len());
+        seed_trivial_eqratio(prog, settings, &self.declared_segments());
+        seed_coord_facts(prog, settings, &self.coord_facts);
+        seed_axioms(&mut prog.col, settings, &self.col_facts[seen.col_facts.len()..],
+            |(a, b, c, p)| ((a.clone(), b.clone(), c.clone()), p),
+            |(a, b, c)| (a.clone(), b.clone(), c.clone(), settings.axiom()));
+        prog.input_para.extend(para_orientations(&self.para_facts[seen.para_facts.len()..]));
+        seed_axioms(&mut prog.para, settings, &self.para_facts[seen.para_facts.len()..],
+            |(a, b, c, d, p)| ((a.clone(), b.clone(), c.clone(), d.clone()), p),
+            |(a, b, c, d)| (a.clone(), b.clone(), c.clone(), d.clone(), settings.axiom()));
+        seed_axioms(&mut prog.perp, settings, &self.perp_facts[seen.perp_facts.len()..],
+            |(a, b, c, d, p)| ((a.clone(), b.clone(), c.clone(), d.clone()), p),
+            |(a, b, c, d)| (a.clone(), b.clone(), c.clone(), d.clone(), settings.axiom()));
+        seed_axioms(&mut prog.cong, settings, &self.cong_facts[seen.cong_facts.len()..],
+            |(a, b, c, d, p)| ((a.clone(), b.clone(), c.clone(), d.clone()), p),
+            |(a, b, c, d)| (a.clone(), b.clone(), c.clone(), d.clone(), settings.axiom()));
+        seed_axioms(&mut prog.eqangle, settings, &self.eqangle_facts[seen.eqangle_facts.len()..],
+            |(a, b, c, d, e, f, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()), p),
+            |(a, b, c, d, e, f)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), settings.axiom()));
+        seed_axioms(&mut prog.cyclic, settings, &self.cyclic_facts[seen.cyclic_facts.len()..],
+            |(a, b, c, d, p)| ((a.clone(), b.clone(), c.clone(), d.clone()), p),
+            |(a, b, c, d)| (a.clone(), b.clone(), c.clone(), d.clone(), settings.axiom()));
+        seed_axioms(&mut prog.sameclock, settings, &self.sameclock_facts[seen.sameclock_facts.len()..],
+            |(a, b, c, d, e, f, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()), p),
+            |(a, b, c, d, e, f)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), settings.axiom()));
+        seed_axioms(&mut prog.midp, settings, &self.midp_facts[seen.midp_facts.len()..],
+            |(a, b, c, p)| ((a.clone(), b.clone(), c.clone()), p),
+            |(a, b, c)| (a.clone(), b.clone(), c.clone(), settings.axiom()));
+        seed_axioms(&mut prog.contri1, settings, &self.contri1_facts[seen.contri1_facts.len()..],
+            |(a, b, c, d, e, f, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()), p),
+            |(a, b, c, d, e, f)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), settings.axiom()));
+        seed_axioms(&mut prog.contri2, settings, &self.contri2_facts[seen.contri2_facts.len()..],
+            |(a, b, c, d, e, f, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()), p),
+            |(a, b, c, d, e, f)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), settings.axiom()));
+        seed_axioms(&mut prog.simtri1, settings, &self.simtri1_facts[seen.simtri1_facts.len()..],
+            |(a, b, c, d, e, f, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()), p),
+            |(a, b, c, d, e, f)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), settings.axiom()));
+        seed_axioms(&mut prog.simtri2, settings, &self.simtri2_facts[seen.simtri2_facts.len()..],
+            |(a, b, c, d, e, f, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()), p),
+            |(a, b, c, d, e, f)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), settings.axiom()));
+        seed_axioms(&mut prog.eqratio, settings, &self.eqratio_facts[seen.eqratio_facts.len()..],
+            |(a, b, c, d, e, f, g, h, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), g.clone(), h.clone()), p),
+            |(a, b, c, d, e, f, g, h)| (a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), g.clone(), h.clone(), settings.axiom()));
+        seed_axioms(&mut prog.aconst, settings, &self.aconst_facts[seen.aconst_facts.len()..],
+            |(a, b, c, m, n, p)| ((a.clone(), b.clone(), c.clone(), *m, *n), p),
+            |(a, b, c, m, n)| (a.clone(), b.clone(), c.clone(), *m, *n, settings.axiom()));
+        seed_axioms(&mut prog.lconst, settings, &self.lconst_facts[seen.lconst_facts.len()..],
+            |(a, b, m, n, p)| ((a.clone(), b.clone(), *m, *n), p),
+            |(a, b, m, n)| (a.clone(), b.clone(), *m, *n, settings.axiom()));
+        seed_axioms(&mut prog.rconst, settings, &self.rconst_facts[seen.rconst_facts.len()..],
+            |(a, b, c, d, m, n, p)| ((a.clone(), b.clone(), c.clone(), d.clone(), *m, *n), p),
+            |(a, b, c, d, m, n)| (a.clone(), b.clone(), c.clone(), d.clone(), *m, *n, settings.axiom()));
+        seed_axioms(&mut prog.tangent, settings, &self.tangent_facts[seen.tangent_facts.len()..],
+            |(a, b, c, d, p)| ((a.clone(), b.clone(), c.clone(), d.clone()), p),
+            |(a, b, c, d)| (a.clone(), b.clone(), c.clone(), d.clone(), settings.axiom()));
+    }
+
+    // Run a seeded program to its fixpoint, or until `max_iterations` or `deadline` stops it,
+    // publish the derived facts and keep the program with the inputs it has seen for
+    // `run_incremental`
+    fn saturate(&mut self, mut prog: AscentProgram, seen: DeductiveDatabase, deadline: Option<Instant>) {
+        // With a zero timeout the program returns after each iteration of its first stratum,
+        // the main loop, or once every stratum is saturated. A later call re-indexes the rows
+        // and continues from them, joining every row again in its first iteration.
+        let expired = || deadline.is_some_and(|d| Instant::now() >= d);
+        let iterations = prog.main_loop_iterations();
+        self.saturated = match self.max_iterations {
+            Some(max) => {
+                let mut saturated = false;
+                for _ in 0..max {
+                    saturated = prog.run_timeout(Duration::ZERO);
+                    if saturated || expired() {
+                        break;
+                    }
+                }
+                saturated
+            }
+            // The program checks the timeout itself after each iteration
+            None => prog.run_timeout(deadline.map_or(Duration::MAX, |d| d.saturating_duration_since(Instant::now()))),
+        };
+
+        // Extract derived results
+        self.derived_col = prog.col.clone();
+        self.derived_para = prog.para.clone();
+        self.derived_perp = prog.perp.clone();
+        self.derived_cong = prog.cong.clone();
+        self.derived_eqangle = prog.eqangle.clone();
+        self.derived_cyclic = prog.cyclic.clone();
+        self.derived_sameclock = prog.sameclock.clone();
+        self.derived_midp = prog.midp.clone();
+        self.derived_contri1 = prog.contri1.clone();
+        self.derived_contri2 = prog.contri2.clone();
+        self.derived_simtri1 = prog.simtri1.clone();
+        self.derived_simtri2 = prog.simtri2.clone();
+        self.derived_eqratio = prog.eqratio.clone();
+        self.derived_aconst = prog.aconst.clone();
+        self.derived_lconst = prog.lconst.clone();
+        self.derived_rconst = prog.rconst.clone();
+        self.derived_eq = prog.eq.clone();
+        self.derived_tangent = prog.tangent.clone();
+        self.iterations = prog.main_loop_iterations() - iterations;
+
+        self.saved = SavedProgram(Some((Box::new(prog), Box::new(seen))));
+    }
+
+    // Numeric truth of a fact over the point coordinates, None when a point has no
+    // coordinates or the predicate has no numeric check
+    fn numeric_value(&self, pred: &str, args: &[String]) -> Option<bool> {
+        let coords = args.iter()
+            .map(|name| self.points.iter().find(|(_, _, n)| n == name).map(|(x, y, _)| (*x, *y)))
+            .collect::<Option<Vec<_>>>()?;
+        check_numeric(pred, &coords, self.epsilon.unwrap_or(0.0))
+    }
+
+    // Segments named by the input facts of segment predicates, each once with its endpoints
+    // in name order
+    fn declared_segments(&self) -> Vec<(String, String)> {
+        let mut segments = Vec::new();
+        for (pred, args, points) in self.input_facts() {
+            if !["para", "perp", "cong", "eqratio", "lconst", "rconst", "tangent"].contains(&pred) {
+                continue;
+            }
+            for pair in args[..points].chunks(2) {
+                let (a, b) = if pair[0] <= pair[1] { (&pair[0], &pair[1]) } else { (&pair[1], &pair[0]) };
+                if a != b && !segments.contains(&(a.clone(), b.clone())) {
+                    segments.push((a.clone(), b.clone()));
+                }
+            }
+        }
+        segments
+    }
+
+    // Every input fact as (predicate, args, number of leading args that are point names),
+    // with numeric args stringified
+    fn input_facts(&self) -> Vec<(&'static str, Vec<String>, usize)> {
+        let mut facts = Vec::new();
+        facts.extend(self.col_facts.iter().map(|(a, b, c)| ("col", vec![a.clone(), b.clone(), c.clone()], 3)));
+        facts.extend(self.para_facts.iter().map(|(a, b, c, d)| ("para", vec![a.clone(), b.clone(), c.clone(), d.clone()], 4)));
+        facts.extend(self.perp_facts.iter().map(|(a, b, c, d)| ("perp", vec![a.clone(), b.clone(), c.clone(), d.clone()], 4)));
+        facts.extend(self.cong_facts.iter().map(|(a, b, c, d)| ("cong", vec![a.clone(), b.clone(), c.clone(), d.clone()], 4)));
+        facts.extend(self.eqangle_facts.iter().map(|(a, b, c, d, e, f)| ("eqangle", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], 6)));
+        facts.extend(self.cyclic_facts.iter().map(|(a, b, c, d)| ("cyclic", vec![a.clone(), b.clone(), c.clone(), d.clone()], 4)));
+        facts.extend(self.sameclock_facts.iter().map(|(a, b, c, d, e, f)| ("sameclock", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], 6)));
+        facts.extend(self.midp_facts.iter().map(|(a, b, c)| ("midp", vec![a.clone(), b.clone(), c.clone()], 3)));
+        facts.extend(self.contri1_facts.iter().map(|(a, b, c, d, e, f)| ("contri1", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], 6)));
+        facts.extend(self.contri2_facts.iter().map(|(a, b, c, d, e, f)| ("contri2", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], 6)));
+        facts.extend(self.simtri1_facts.iter().map(|(a, b, c, d, e, f)| ("simtri1", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], 6)));
+        facts.extend(self.simtri2_facts.iter().map(|(a, b, c, d, e, f)| ("simtri2", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], 6)));
+        facts.extend(self.eqratio_facts.iter().map(|(a, b, c, d, e, f, g, h)| ("eqratio", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), g.clone(), h.clone()], 8)));
+        facts.extend(self.aconst_facts.iter().map(|(a, b, c, m, n)| ("aconst", vec![a.clone(), b.clone(), c.clone(), m.to_string(), n.to_string()], 3)));
+        facts.extend(self.lconst_facts.iter().map(|(a, b, m, n)| ("lconst", vec![a.clone(), b.clone(), m.to_string(), n.to_string()], 2)));
+        facts.extend(self.rconst_facts.iter().map(|(a, b, c, d, m, n)| ("rconst", vec![a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()], 4)));
+        facts.extend(self.tangent_facts.iter().map(|(a, b, o, p)| ("tangent", vec![a.clone(), b.clone(), o.clone(), p.clone()], 4)));
+        facts
+    }
+
+    // Every derived fact as (predicate, args, provenance), with numeric args stringified
+    fn derived_facts(&self) -> Vec<(&'static str, Vec<String>, &Provenance)> {
+        let mut facts = Vec::new();
+        facts.extend(self.derived_col.iter().map(|(a, b, c, p)| ("col", vec![a.clone(), b.clone(), c.clone()], p)));
+        facts.extend(self.derived_para.iter().map(|(a, b, c, d, p)| ("para", vec![a.clone(), b.clone(), c.clone(), d.clone()], p)));
+        facts.extend(self.derived_perp.iter().map(|(a, b, c, d, p)| ("perp", vec![a.clone(), b.clone(), c.clone(), d.clone()], p)));
+        facts.extend(self.derived_cong.iter().map(|(a, b, c, d, p)| ("cong", vec![a.clone(), b.clone(), c.clone(), d.clone()], p)));
+        facts.extend(self.derived_eqangle.iter().map(|(a, b, c, d, e, f, p)| ("eqangle", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], p)));
+        facts.extend(self.derived_cyclic.iter().map(|(a, b, c, d, p)| ("cyclic", vec![a.clone(), b.clone(), c.clone(), d.clone()], p)));
+        facts.extend(self.derived_sameclock.iter().map(|(a, b, c, d, e, f, p)| ("sameclock", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], p)));
+        facts.extend(self.derived_midp.iter().map(|(a, b, c, p)| ("midp", vec![a.clone(), b.clone(), c.clone()], p)));
+        facts.extend(self.derived_contri1.iter().map(|(a, b, c, d, e, f, p)| ("contri1", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], p)));
+        facts.extend(self.derived_contri2.iter().map(|(a, b, c, d, e, f, p)| ("contri2", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], p)));
+        facts.extend(self.derived_simtri1.iter().map(|(a, b, c, d, e, f, p)| ("simtri1", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], p)));
+        facts.extend(self.derived_simtri2.iter().map(|(a, b, c, d, e, f, p)| ("simtri2", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone()], p)));
+        facts.extend(self.derived_eqratio.iter().map(|(a, b, c, d, e, f, g, h, p)| ("eqratio", vec![a.clone(), b.clone(), c.clone(), d.clone(), e.clone(), f.clone(), g.clone(), h.clone()], p)));
+        facts.extend(self.derived_aconst.iter().map(|(a, b, c, m, n, p)| ("aconst", vec![a.clone(), b.clone(), c.clone(), m.to_string(), n.to_string()], p)));
+        facts.extend(self.derived_lconst.iter().map(|(a, b, m, n, p)| ("lconst", vec![a.clone(), b.clone(), m.to_string(), n.to_string()], p)));
+        facts.extend(self.derived_rconst.iter().map(|(a, b, c, d, m, n, p)| ("rconst", vec![a.clone(), b.clone(), c.clone(), d.clone(), m.to_string(), n.to_string()], p)));
+        facts.extend(self.derived_eq.iter().map(|(a, b, p)| ("eq", vec![a.clone(), b.clone()], p)));
+        facts.extend(self.derived_tangent.iter().map(|(a, b, o, p, prov)| ("tangent", vec![a.clone(), b.clone(), o.clone(), p.clone()], prov)));
+        facts
+    }
+
+    // Provenance of a derived fact, trying its symmetric argument orders in turn
+    fn find_derived(&self, pred: &str, args: Vec<String>) -> Option<&Provenance> {
+        let facts = self.derived_facts();
+        symmetric_variants(pred, args).into_iter().find_map(|variant| {
+            facts.iter()
+                .find(|(p, a, _)| *p == pred && *a == variant)
+                .map(|(_, _, prov)| *prov)
+        })
+    }
+
+    // Minimum proof depth of every derived fact along with the derivation achieving it.
+    // Axioms have depth 0; facts only derivable through a cycle never get a depth.
+    fn proof_depths(&self) -> HashMap<String, (usize, &Derivation)> {
+        let facts: Vec<(String, &Provenance)> = self.derived_facts()
+            .into_iter()
+            .map(|(pred, args, prov)| (fact_id(pred, args), prov))
+            .collect();
+
+        let mut depths: HashMap<String, (usize, &Derivation)> = HashMap::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (id, prov) in &facts {
+                for derivation in &prov.derivations {
+                    let parent_depths: Option<Vec<usize>> = derivation.parents.iter()
+                        .map(|parent| depths.get(parent).map(|(depth, _)| *depth))
+                        .collect();
+                    let Some(parent_depths) = parent_depths else { continue };
+
+                    let depth = if derivation.rule == "axiom" {
+                        0
+                    } else {
+                        parent_depths.into_iter().max().unwrap_or(0) + 1
+                    };
+                    if depths.get(id).is_none_or(|(best, _)| depth < *best) {
+                        depths.insert(id.clone(), (depth, derivation));
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        depths
+    }
+
+    // Facts needed to prove a fact using the minimum-depth derivation of every step, each
+    // listed once and ordered by depth so parents always come before the facts they prove
+    fn proof_steps<'a>(&self, depths: &'a HashMap<String, (usize, &Derivation)>, id: &'a str) -> Option<Vec<&'a str>> {
+        depths.get(id)?;
+
+        let mut needed: HashSet<&str> = HashSet::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if needed.insert(current) {
+                stack.extend(depths[current].1.parents.iter().map(|p| p.as_str()));
+            }
+        }
+
+        let mut ordered: Vec<&str> = needed.into_iter().collect();
+        ordered.sort_by_key(|f| (depths[*f].0, *f));
+        Some(ordered)
+    }
+
+    // Node of `proof_tree` for a fact that has a proof depth, with its premises expanded
+    fn proof_tree_node(py: Python<'_>, depths: &HashMap<String, (usize, &Derivation)>, id: &str) -> PyResult<PyObject> {
+        let derivation = depths[id].1;
+        let premises = PyList::empty_bound(py);
+        for parent in &derivation.parents {
+            premises.append(Self::proof_tree_node(py, depths, parent)?)?;
+        }
+
+        let node = PyDict::new_bound(py);
+        node.set_item("fact", id)?;
+        node.set_item("rule", &derivation.rule)?;
+        node.set_item("premises", premises)?;
+        Ok(node.into_any().unbind())
+    }
+
+    // Numbered proof of a fact using the minimum-depth derivation of every step
+    fn proof_text(&self, depths: &HashMap<String, (usize, &Derivation)>, id: &str) -> Option<String> {
+        let ordered = self.proof_steps(depths, id)?;
+        let numbering: HashMap<&str, usize> = ordered.iter()
+            .enumerate()
+            .map(|(i, f)| (*f, i + 1))
+            .collect();
+
+        let lines: Vec<String> = ordered.iter()
+            .map(|f| {
+                let derivation = depths[*f].1;
+                let parents: Vec<String> = derivation.parents.iter()
+                    .map(|p| format!("[{}]", numbering[p.as_str()]))
+                    .collect();
+                format!("[{}] {:<30} | {} {}", numbering[f], f, derivation.rule, parents.join(","))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+
+        Some(lines.join("\n"))
+    }
+
+    // Copy of the database with only the points, input facts and settings
+    fn without_derived(&self) -> Self {
+        DeductiveDatabase {
+            points: self.points.clone(),
+            col_facts: self.col_facts.clone(),
+            para_facts: self.para_facts.clone(),
+            perp_facts: self.perp_facts.clone(),
+            cong_facts: self.cong_facts.clone(),
+            eqangle_facts: self.eqangle_facts.clone(),
+            cyclic_facts: self.cyclic_facts.clone(),
+            sameclock_facts: self.sameclock_facts.clone(),
+            midp_facts: self.midp_facts.clone(),
+            contri1_facts: self.contri1_facts.clone(),
+            contri2_facts: self.contri2_facts.clone(),
+            simtri1_facts: self.simtri1_facts.clone(),
+            simtri2_facts: self.simtri2_facts.clone(),
+            eqratio_facts: self.eqratio_facts.clone(),
+            aconst_facts: self.aconst_facts.clone(),
+            lconst_facts: self.lconst_facts.clone(),
+            rconst_facts: self.rconst_facts.clone(),
+            tangent_facts: self.tangent_facts.clone(),
+            input_ids: self.input_ids.clone(),
+            coord_facts: self.coord_facts.clone(),
+            enabled_rules: self.enabled_rules.clone(),
+            disabled_rules: self.disabled_rules.clone(),
+            max_derivations_per_fact: self.max_derivations_per_fact,
+            max_iterations: self.max_iterations,
+            epsilon: self.epsilon,
+            ..Default::default()
+        }
     }
 }
 
 #[pymodule]
 fn ascent_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<DeductiveDatabase>()?;
+    m.add_class::<Fact>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,1801 @@
+use super::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+// Database over the given integer points
+fn db(points: &[(&str, i64, i64)]) -> DeductiveDatabase {
+    let mut db = DeductiveDatabase::default();
+    for (name, x, y) in points {
+        db.add_point(Coord::Int(*x), Coord::Int(*y), name.to_string()).unwrap();
+    }
+    db
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+// Add an input fact given as a predicate and its arguments, constants included
+fn add(db: &mut DeductiveDatabase, pred: &str, args: &[&str]) {
+    let s = strings(args);
+    let int = |i: usize| s[i].parse::<i32>().unwrap();
+    match (pred, s.len()) {
+        ("col", 3) => db.add_col(s[0].clone(), s[1].clone(), s[2].clone()),
+        ("para", 4) => db.add_para(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone()),
+        ("perp", 4) => db.add_perp(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone()),
+        ("cong", 4) => db.add_cong(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone()),
+        ("cyclic", 4) => db.add_cyclic(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone()),
+        ("tangent", 4) => db.add_tangent(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone()),
+        ("midp", 3) => db.add_midp(s[0].clone(), s[1].clone(), s[2].clone()),
+        ("eqangle", 6) => db.add_eqangle(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), s[4].clone(), s[5].clone()),
+        ("sameclock", 6) => db.add_sameclock(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), s[4].clone(), s[5].clone()),
+        ("contri1", 6) => db.add_contri1(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), s[4].clone(), s[5].clone()),
+        ("contri2", 6) => db.add_contri2(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), s[4].clone(), s[5].clone()),
+        ("simtri1", 6) => db.add_simtri1(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), s[4].clone(), s[5].clone()),
+        ("simtri2", 6) => db.add_simtri2(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), s[4].clone(), s[5].clone()),
+        ("eqratio", 8) => db.add_eqratio(
+            s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(),
+            s[4].clone(), s[5].clone(), s[6].clone(), s[7].clone(),
+        ),
+        ("aconst", 5) => db.add_aconst(s[0].clone(), s[1].clone(), s[2].clone(), int(3), int(4)),
+        ("lconst", 4) => db.add_lconst(s[0].clone(), s[1].clone(), int(2), int(3)),
+        ("rconst", 6) => db.add_rconst(s[0].clone(), s[1].clone(), s[2].clone(), s[3].clone(), int(4), int(5)),
+        _ => panic!("no input fact {}({})", pred, args.join(", ")),
+    }
+}
+
+// Hash of every derived fact along with its derivations, independent of their order
+fn fact_set_hash(db: &DeductiveDatabase) -> u64 {
+    let mut facts = db.get_all_facts();
+    facts.sort();
+    let mut hasher = DefaultHasher::new();
+    facts.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Whether one of the derivations of the fact cites `rule`
+fn derived_by(db: &DeductiveDatabase, rule: &str, pred: &str, args: &[&str]) -> bool {
+    db.find_derived(pred, strings(args))
+        .is_some_and(|prov| prov.derivations.iter().any(|d| d.rule == rule))
+}
+
+#[test]
+fn orthocenter_reflection_lies_on_circumcircle() {
+    // h is the orthocenter of abc and p its reflection over bc
+    let mut db = db(&[("a", 0, 6), ("b", -2, 0), ("c", 6, 0), ("h", 0, 2), ("p", 0, -2)]);
+    add(&mut db, "perp", &["a", "h", "b", "c"]);
+    add(&mut db, "perp", &["b", "h", "a", "c"]);
+    add(&mut db, "cyclic", &["a", "b", "c", "p"]);
+    add(&mut db, "col", &["a", "h", "p"]);
+    db.run();
+    assert!(!derived_by(&db, "orthocenter_reflection", "cong", &["b", "h", "b", "p"]));
+
+    db.enable_rule("orthocenter_reflection".to_string());
+    db.run();
+    assert!(derived_by(&db, "orthocenter_reflection", "cong", &["b", "h", "b", "p"]));
+    assert!(derived_by(&db, "orthocenter_reflection", "cong", &["c", "h", "c", "p"]));
+}
+
+#[test]
+fn bincode_round_trip_keeps_derived_facts() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    db.run();
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let data = db.to_bincode(py, true).unwrap();
+        assert_eq!(data.as_bytes()[0], 1);
+        let loaded = DeductiveDatabase::from_bincode(data.as_bytes()).unwrap();
+        assert_eq!(fact_set_hash(&loaded), fact_set_hash(&db));
+
+        let inputs = db.to_bincode(py, false).unwrap();
+        assert!(DeductiveDatabase::from_bincode(inputs.as_bytes()).unwrap().get_all_facts().is_empty());
+
+        let mut stale = data.as_bytes().to_vec();
+        stale[0] = 0;
+        assert!(DeductiveDatabase::from_bincode(&stale).is_err());
+    });
+}
+
+#[test]
+fn tangent_two_chords_make_equal_angles_with_tangent() {
+    // pt touches the circle centered o at p; chords pa and pb subtend equal angles at c
+    let mut db = db(&[("o", 0, 0), ("p", 5, 0), ("t", 5, 5), ("a", 3, 4), ("b", 3, -4), ("c", -5, 0)]);
+    add(&mut db, "perp", &["o", "p", "p", "t"]);
+    add(&mut db, "cong", &["o", "p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "b"]);
+    add(&mut db, "cyclic", &["p", "a", "b", "c"]);
+    add(&mut db, "eqangle", &["p", "c", "a", "b", "c", "p"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_two_chords", "eqangle", &["t", "p", "a", "b", "p", "t"]));
+}
+
+#[test]
+fn midp_line_extend_through_midpoint() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("m", 2, 0), ("p", 7, 0)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "col", &["p", "a", "m"]);
+    db.run();
+    assert!(derived_by(&db, "midp_line_extend", "col", &["p", "a", "b"]));
+    assert!(derived_by(&db, "midp_line_extend", "col", &["p", "m", "b"]));
+}
+
+#[test]
+fn sas_right_cong_on_two_right_triangles() {
+    // Right angles at a, d and g; def is a translate of abc and ghi a mirror image
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 3, 0), ("c", 0, 4),
+        ("d", 10, 0), ("e", 13, 0), ("f", 10, 4),
+        ("g", 20, 0), ("h", 17, 0), ("i", 20, 4),
+    ]);
+    add(&mut db, "perp", &["b", "a", "a", "c"]);
+    add(&mut db, "perp", &["e", "d", "d", "f"]);
+    add(&mut db, "perp", &["h", "g", "g", "i"]);
+    add(&mut db, "cong", &["a", "b", "d", "e"]);
+    add(&mut db, "cong", &["a", "c", "d", "f"]);
+    add(&mut db, "cong", &["a", "b", "g", "h"]);
+    add(&mut db, "cong", &["a", "c", "g", "i"]);
+    db.run();
+    assert!(derived_by(&db, "sas_right_cong", "contri1", &["a", "b", "c", "d", "e", "f"]));
+    assert!(derived_by(&db, "sas_right_cong", "contri2", &["a", "b", "c", "g", "h", "i"]));
+    assert!(!derived_by(&db, "sas_right_cong", "contri1", &["a", "b", "c", "g", "h", "i"]));
+}
+
+#[test]
+fn equal_aconst_para_from_equal_constant_angles() {
+    // ∠xab = π/4 and ∠xcd = 5π/4, the same direction modulo π
+    let mut db = db(&[("x", 0, 0), ("a", 2, 0), ("b", 3, 1), ("c", 5, 0), ("d", 6, 1)]);
+    add(&mut db, "aconst", &["x", "a", "b", "1", "4"]);
+    add(&mut db, "aconst", &["x", "c", "d", "5", "4"]);
+    add(&mut db, "col", &["x", "a", "c"]);
+    db.run();
+    assert!(derived_by(&db, "equal_aconst_para", "para", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn coverage_counts_true_and_derived_facts() {
+    let mut db = db(&[("a", 0, 0), ("b", 2, 0), ("c", 2, 2), ("d", 0, 2)]);
+    add(&mut db, "para", &["a", "b", "d", "c"]);
+    db.run();
+    let coverage = db.coverage().unwrap();
+    let (_, true_count, derived_count) = coverage.iter().find(|(pred, _, _)| pred == "para").unwrap();
+    assert!(*derived_count >= 1);
+    assert!(derived_count <= true_count);
+
+    let mut big = DeductiveDatabase::default();
+    for i in 0..=MAX_COVERAGE_POINTS as i64 {
+        big.add_point(Coord::Int(i), Coord::Int(i * i), format!("p{}", i)).unwrap();
+    }
+    assert!(big.coverage().is_err());
+}
+
+#[test]
+fn equal_cevian_ratios_at_cevian_intersection() {
+    // cd:db = ce:ea = 1:2, and the cevians ad and be meet at p
+    let mut db = db(&[("a", 0, 12), ("b", 12, 0), ("c", 0, 0), ("d", 4, 0), ("e", 0, 4), ("p", 3, 3)]);
+    add(&mut db, "col", &["b", "d", "c"]);
+    add(&mut db, "col", &["a", "e", "c"]);
+    add(&mut db, "eqratio", &["c", "d", "d", "b", "c", "e", "e", "a"]);
+    add(&mut db, "col", &["a", "p", "d"]);
+    add(&mut db, "col", &["b", "p", "e"]);
+    db.run();
+    assert!(derived_by(&db, "equal_cevian_ratios", "eqratio", &["p", "d", "p", "a", "p", "e", "p", "b"]));
+}
+
+#[test]
+fn equal_cevian_ratios_needs_feet_inside_the_sides() {
+    // cd:db = ce:ea = 1:3 but d lies beyond c, so pd:pa = 1:2 while pe:pb = 1:4
+    let mut db = db(&[("a", 0, 12), ("b", 12, 0), ("c", 0, 0), ("d", -6, 0), ("e", 0, 3), ("p", -4, 4)]);
+    add(&mut db, "col", &["b", "d", "c"]);
+    add(&mut db, "col", &["a", "e", "c"]);
+    add(&mut db, "eqratio", &["c", "d", "d", "b", "c", "e", "e", "a"]);
+    add(&mut db, "col", &["a", "p", "d"]);
+    add(&mut db, "col", &["b", "p", "e"]);
+    db.run();
+    assert!(!derived_by(&db, "equal_cevian_ratios", "eqratio", &["p", "d", "p", "a", "p", "e", "p", "b"]));
+}
+
+#[test]
+fn isosceles_trapezoid_is_cyclic() {
+    let mut db = db(&[("a", 0, 0), ("b", 6, 0), ("c", 4, 3), ("d", 2, 3)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "cong", &["a", "d", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "isosceles_trapezoid_cyclic", "cyclic", &["a", "b", "c", "d"]));
+
+    // A parallelogram also has ab ∥ cd and equal legs but is not cyclic
+    let mut db = self::db(&[("a", 0, 0), ("b", 4, 0), ("c", 6, 3), ("d", 2, 3)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "cong", &["a", "d", "b", "c"]);
+    db.run();
+    assert!(!derived_by(&db, "isosceles_trapezoid_cyclic", "cyclic", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn tangent_chord_perp_on_a_diameter() {
+    // pa is a diameter of the circle centered o and pt the tangent at p
+    let mut db = db(&[("o", 0, 0), ("p", -5, 0), ("a", 5, 0), ("c", 3, 4), ("t", -5, 5)]);
+    add(&mut db, "eqangle", &["t", "p", "a", "p", "c", "a"]);
+    add(&mut db, "col", &["p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "c"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_chord_perp", "perp", &["t", "p", "p", "a"]));
+}
+
+#[test]
+fn explain_contradiction_shows_both_proofs() {
+    // ab ∥ cd ∥ ef is inconsistent with ab ⟂ ef
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    add(&mut db, "perp", &["a", "b", "e", "f"]);
+    db.run();
+    assert!(db.contradictions().contains(&(fact_id("para", ["a", "b", "e", "f"]), fact_id("perp", ["a", "b", "e", "f"]))));
+
+    let (para, perp) = db.explain_contradiction(
+        ("para".to_string(), strings(&["a", "b", "e", "f"])),
+        ("perp".to_string(), strings(&["a", "b", "e", "f"])),
+    );
+    assert!(para.contains(&fact_id("para", ["c", "d", "e", "f"])));
+    assert!(perp.contains("axiom"));
+
+    let (_, missing) = db.explain_contradiction(
+        ("para".to_string(), strings(&["a", "b", "e", "f"])),
+        ("cyclic".to_string(), strings(&["a", "b", "c", "d"])),
+    );
+    assert!(missing.ends_with("was not derived"));
+}
+
+#[test]
+fn midsegment_is_parallel_to_the_base() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 2, 6), ("m", 2, 0), ("n", 1, 3)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "midp", &["n", "a", "c"]);
+    db.run();
+    assert!(derived_by(&db, "midsegment", "para", &["m", "n", "b", "c"]));
+}
+
+#[test]
+fn midsegment_from_both_endpoint_orders() {
+    // The second order of m is a duplicate input, yet the midsegment through b needs it
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 2, 6), ("m", 2, 0), ("k", 3, 3)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "midp", &["m", "b", "a"]);
+    add(&mut db, "midp", &["k", "b", "c"]);
+    assert_eq!(db.midp_facts.len(), 2);
+    db.run();
+    assert!(derived_by(&db, "sym", "midp", &["m", "b", "a"]));
+    assert!(derived_by(&db, "midsegment", "para", &["m", "k", "a", "c"]));
+}
+
+#[test]
+fn midsegment_needs_a_triangle() {
+    // a, b and c on one line, so mn and bc lie on it too
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 6, 0), ("m", 2, 0), ("n", 3, 0)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "midp", &["n", "a", "c"]);
+    db.run();
+    assert!(!derived_by(&db, "midsegment", "para", &["m", "n", "b", "c"]));
+}
+
+#[test]
+fn equal_arc_triangles_in_one_circle() {
+    // def is abc rotated a quarter turn about the center of their common circle
+    let mut db = db(&[("a", 5, 0), ("b", 3, 4), ("c", -3, 4), ("d", 0, 5), ("e", -4, 3), ("f", -4, -3)]);
+    add(&mut db, "simtri1", &["a", "b", "c", "d", "e", "f"]);
+    add(&mut db, "cyclic", &["a", "b", "c", "d"]);
+    add(&mut db, "cyclic", &["a", "b", "c", "e"]);
+    add(&mut db, "cyclic", &["a", "b", "c", "f"]);
+    db.run();
+    assert!(derived_by(&db, "equal_arc_triangles", "contri1", &["a", "b", "c", "d", "e", "f"]));
+}
+
+#[test]
+fn common_parallel_derives_para() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5), ("g", 0, 7), ("h", 4, 7)]);
+    add(&mut db, "para", &["a", "b", "e", "f"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    add(&mut db, "para", &["g", "h", "c", "d"]);
+    db.disable_rule("para_trans".to_string());
+    db.run();
+    assert!(derived_by(&db, "common_parallel", "para", &["a", "b", "c", "d"]));
+    assert!(derived_by(&db, "common_parallel", "para", &["g", "h", "e", "f"]));
+    assert!(!derived_by(&db, "common_parallel", "para", &["a", "b", "a", "b"]));
+    // ab and gh share no input line, so reaching ab ∥ gh takes two steps
+    assert!(!derived_by(&db, "common_parallel", "para", &["a", "b", "g", "h"]));
+    assert!(!db.get_para().iter().any(|(.., derivations)| derivations.iter().any(|(rule, _)| rule == "para_trans")));
+
+    db.enable_rule("para_trans".to_string());
+    db.run();
+    assert!(derived_by(&db, "para_trans", "para", &["a", "b", "g", "h"]));
+}
+
+#[test]
+fn midp_col_in_every_order() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("m", 2, 0)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    db.run();
+    let cols: Vec<[String; 3]> = db.get_col().into_iter().map(|(a, b, c, _)| [a, b, c]).collect();
+    for order in [["a", "m", "b"], ["b", "m", "a"], ["a", "b", "m"]] {
+        assert!(cols.contains(&order.map(String::from)));
+    }
+    assert!(derived_by(&db, "midp_col", "col", &["a", "m", "b"]));
+}
+
+#[test]
+fn median_half_hypotenuse_gives_cong_and_ratio() {
+    // Right angle at a and m the midpoint of the hypotenuse bc
+    let mut db = db(&[("a", 0, 0), ("b", 6, 0), ("c", 0, 8), ("m", 3, 4)]);
+    add(&mut db, "perp", &["b", "a", "a", "c"]);
+    add(&mut db, "midp", &["m", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "median_half_hypotenuse", "cong", &["m", "a", "m", "b"]));
+    assert!(derived_by(&db, "median_half_hypotenuse", "eqratio", &["m", "a", "b", "c", "m", "b", "b", "c"]));
+    assert!(derived_by(&db, "median_half_hypotenuse", "rconst", &["m", "a", "b", "c", "1", "2"]));
+}
+
+#[test]
+fn midp_cong_cites_the_midpoint() {
+    let mut db = db(&[("A", 0, 0), ("B", 4, 0), ("M", 2, 0)]);
+    add(&mut db, "midp", &["M", "A", "B"]);
+    db.run();
+    let (.., derivations) = db.get_cong().into_iter()
+        .find(|(a, b, c, d, _)| [a, b, c, d] == ["A", "M", "B", "M"])
+        .unwrap();
+    assert!(derivations.contains(&("midp_cong".to_string(), vec![fact_id("midp", ["M", "A", "B"])])));
+}
+
+#[test]
+fn triangle_angle_sum_gives_third_angle() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3)]);
+    add(&mut db, "aconst", &["a", "b", "c", "1", "3"]);
+    add(&mut db, "aconst", &["b", "c", "a", "1", "4"]);
+    db.run();
+    // π - π/3 - π/4 = 5π/12
+    assert!(derived_by(&db, "triangle_angle_sum", "aconst", &["c", "a", "b", "5", "12"]));
+}
+
+#[test]
+fn parallelism_is_transitive() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    db.run();
+    for (l1, l2) in [(["a", "b"], ["c", "d"]), (["c", "d"], ["e", "f"]), (["a", "b"], ["e", "f"])] {
+        assert!(derived_by(&db, "sym", "para", &[l2[0], l2[1], l1[0], l1[1]]));
+        assert!(derived_by(&db, "sym", "para", &[l1[1], l1[0], l2[0], l2[1]]));
+    }
+    assert!(derived_by(&db, "para_trans", "para", &["a", "b", "e", "f"]));
+}
+
+#[test]
+fn supplementary_opposite_angles_are_cyclic() {
+    // ac is a diameter, so the opposite angles at b and d are both right
+    let mut db = db(&[("a", 5, 0), ("b", 3, 4), ("c", -5, 0), ("d", 0, -5)]);
+    add(&mut db, "aconst", &["a", "b", "c", "1", "2"]);
+    add(&mut db, "aconst", &["a", "d", "c", "1", "2"]);
+    db.run();
+    assert!(derived_by(&db, "supplementary_opposite_cyclic", "cyclic", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn perp_perp_para_from_two_altitudes() {
+    // ah and ef are both perpendicular to bc
+    let mut db = db(&[("a", 1, 3), ("b", 0, 0), ("c", 4, 0), ("h", 1, 0), ("e", 3, 5), ("f", 3, 1)]);
+    add(&mut db, "perp", &["a", "h", "b", "c"]);
+    add(&mut db, "perp", &["e", "f", "c", "b"]);
+    db.run();
+    assert!(derived_by(&db, "perp_perp_para", "para", &["a", "h", "e", "f"]));
+}
+
+#[test]
+fn incircle_tangent_at_touch_point() {
+    // Incircle of the right triangle abc centered i touches ab at z and bc at x
+    let mut db = db(&[("a", 0, 3), ("b", 0, 0), ("c", 4, 0), ("i", 1, 1), ("z", 0, 1), ("x", 1, 0)]);
+    add(&mut db, "eqangle", &["a", "b", "i", "i", "b", "c"]);
+    add(&mut db, "perp", &["i", "z", "a", "b"]);
+    add(&mut db, "col", &["z", "a", "b"]);
+    add(&mut db, "cong", &["i", "z", "i", "x"]);
+    add(&mut db, "col", &["x", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "incircle_tangent", "perp", &["i", "x", "b", "c"]));
+}
+
+#[test]
+fn para_perp_perp_through_a_right_angle() {
+    // ab ⟂ ac at a, and de ∥ ab is perpendicular to ac as well
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 3), ("d", 1, 1), ("e", 3, 1)]);
+    add(&mut db, "perp", &["a", "b", "a", "c"]);
+    add(&mut db, "para", &["d", "e", "a", "b"]);
+    db.run();
+    assert!(derived_by(&db, "para_perp_perp", "perp", &["d", "e", "a", "c"]));
+}
+
+#[test]
+fn incircle_tangent_segments_from_each_vertex() {
+    // Incircle of abc centered i touches ab at z, bc at x and ca at y
+    let mut db = db(&[
+        ("a", 0, 15), ("b", 0, 0), ("c", 20, 0),
+        ("i", 5, 5), ("z", 0, 5), ("x", 5, 0), ("y", 8, 9),
+    ]);
+    add(&mut db, "perp", &["i", "z", "a", "b"]);
+    add(&mut db, "perp", &["i", "x", "b", "c"]);
+    add(&mut db, "perp", &["i", "y", "c", "a"]);
+    add(&mut db, "col", &["z", "a", "b"]);
+    add(&mut db, "col", &["x", "b", "c"]);
+    add(&mut db, "col", &["y", "c", "a"]);
+    add(&mut db, "cong", &["i", "z", "i", "x"]);
+    add(&mut db, "cong", &["i", "x", "i", "y"]);
+    add(&mut db, "cong", &["i", "y", "i", "z"]);
+    db.run();
+    assert!(derived_by(&db, "incircle_tangent_segments", "cong", &["b", "z", "b", "x"]));
+    assert!(derived_by(&db, "incircle_tangent_segments", "cong", &["c", "x", "c", "y"]));
+    assert!(derived_by(&db, "incircle_tangent_segments", "cong", &["a", "y", "a", "z"]));
+}
+
+#[test]
+fn eqangle_trans_along_a_chain() {
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 4, 0), ("c", 1, 3),
+        ("d", 10, 0), ("e", 14, 0), ("f", 11, 3),
+        ("g", 20, 0), ("h", 24, 0), ("i", 21, 3),
+    ]);
+    add(&mut db, "eqangle", &["a", "b", "c", "d", "e", "f"]);
+    add(&mut db, "eqangle", &["d", "e", "f", "g", "h", "i"]);
+    db.run();
+    assert!(derived_by(&db, "eqangle_trans", "eqangle", &["a", "b", "c", "g", "h", "i"]));
+}
+
+#[test]
+fn tangent_parallel_to_chord_bisects_the_arc() {
+    // pt touches the circle centered o at p and is parallel to the chord ab
+    let mut db = db(&[("o", 0, 0), ("p", 0, 5), ("t", 5, 5), ("a", -4, 3), ("b", 4, 3)]);
+    add(&mut db, "perp", &["o", "p", "p", "t"]);
+    add(&mut db, "para", &["p", "t", "a", "b"]);
+    add(&mut db, "cong", &["o", "p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "b"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_parallel_chord", "cong", &["p", "a", "p", "b"]));
+}
+
+#[test]
+fn simtri_eqratio_after_aa_similarity() {
+    // def is abc scaled by 2, ghi is its mirror image
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 4, 0), ("c", 0, 2),
+        ("d", 10, 0), ("e", 18, 0), ("f", 10, 4),
+        ("g", 30, 0), ("h", 22, 0), ("i", 30, 4),
+    ]);
+    add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+    add(&mut db, "eqangle", &["b", "c", "a", "e", "f", "d"]);
+    add(&mut db, "eqangle", &["b", "a", "c", "i", "g", "h"]);
+    add(&mut db, "eqangle", &["b", "c", "a", "g", "i", "h"]);
+    db.run();
+    assert!(derived_by(&db, "aa_sim", "simtri1", &["a", "b", "c", "d", "e", "f"]));
+    assert!(derived_by(&db, "aa_sim", "simtri2", &["a", "b", "c", "g", "h", "i"]));
+
+    let ratios: Vec<[String; 8]> = db.get_eqratio().into_iter()
+        .map(|(a, b, c, d, e, f, g, h, _)| [a, b, c, d, e, f, g, h])
+        .collect();
+    for ratio in [
+        ["a", "b", "d", "e", "b", "c", "e", "f"],
+        ["a", "b", "d", "e", "a", "c", "d", "f"],
+        ["a", "b", "g", "h", "b", "c", "h", "i"],
+        ["a", "b", "g", "h", "a", "c", "g", "i"],
+    ] {
+        assert!(ratios.contains(&ratio.map(String::from)));
+        assert!(derived_by(&db, "simtri_eqratio", "eqratio", &ratio));
+    }
+}
+
+#[test]
+fn proof_dag_shares_subproofs() {
+    // Both medians ma and mc of the right triangle come from the same two axioms, and the
+    // circle centered m through a, b, c, d needs both
+    let mut db = db(&[("a", 0, 0), ("b", 6, 0), ("c", 0, 8), ("m", 3, 4), ("d", 8, 4)]);
+    add(&mut db, "perp", &["b", "a", "a", "c"]);
+    add(&mut db, "midp", &["m", "b", "c"]);
+    add(&mut db, "cong", &["m", "a", "m", "d"]);
+    db.run();
+
+    let dag = db.proof_dag("cyclic".to_string(), strings(&["a", "b", "c", "d"]));
+    let ids: Vec<&String> = dag.iter().map(|(id, _, _)| id).collect();
+    let unique: HashSet<&String> = ids.iter().copied().collect();
+    assert_eq!(ids.len(), unique.len());
+    for (_, _, parents) in &dag {
+        assert!(parents.iter().all(|p| unique.contains(p)));
+    }
+
+    let midp = fact_id("midp", ["m", "b", "c"]);
+    assert_eq!(dag.iter().filter(|(_, _, parents)| parents.contains(&midp)).count(), 2);
+}
+
+#[test]
+fn contri_cong_after_asa_congruence() {
+    // def is a translate of abc
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3), ("d", 10, 0), ("e", 14, 0), ("f", 11, 3)]);
+    add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+    add(&mut db, "eqangle", &["c", "b", "a", "f", "e", "d"]);
+    add(&mut db, "cong", &["a", "b", "d", "e"]);
+    db.run();
+    assert!(derived_by(&db, "asa_cong", "contri1", &["a", "b", "c", "d", "e", "f"]));
+
+    let congs: Vec<[String; 4]> = db.get_cong().into_iter().map(|(a, b, c, d, _)| [a, b, c, d]).collect();
+    for sides in [["a", "b", "d", "e"], ["b", "c", "e", "f"], ["a", "c", "d", "f"]] {
+        assert!(congs.contains(&sides.map(String::from)));
+        assert!(derived_by(&db, "contri_cong", "cong", &sides));
+    }
+}
+
+#[test]
+fn similar_bisectors_scale_with_the_triangles() {
+    // def is abc scaled by 2, ghi its mirror image; ax, dy and gz bisect the apex angles
+    let mut db = db(&[
+        ("a", 0, 3), ("b", -4, 0), ("c", 4, 0), ("x", 0, 0),
+        ("d", 10, 6), ("e", 2, 0), ("f", 18, 0), ("y", 10, 0),
+        ("g", 30, 6), ("h", 38, 0), ("i", 22, 0), ("z", 30, 0),
+    ]);
+    add(&mut db, "simtri1", &["a", "b", "c", "d", "e", "f"]);
+    add(&mut db, "simtri2", &["a", "b", "c", "g", "h", "i"]);
+    add(&mut db, "eqangle", &["b", "a", "x", "x", "a", "c"]);
+    add(&mut db, "col", &["x", "b", "c"]);
+    add(&mut db, "eqangle", &["e", "d", "y", "y", "d", "f"]);
+    add(&mut db, "col", &["y", "e", "f"]);
+    add(&mut db, "eqangle", &["h", "g", "z", "z", "g", "i"]);
+    add(&mut db, "col", &["z", "h", "i"]);
+    db.run();
+    assert!(derived_by(&db, "similar_bisectors", "eqratio", &["a", "x", "d", "y", "a", "b", "d", "e"]));
+    assert!(derived_by(&db, "similar_bisectors", "eqratio", &["a", "x", "g", "z", "a", "b", "g", "h"]));
+}
+
+#[test]
+fn contri_eqangle_closes_an_aa_similarity() {
+    // ASA gives abc ≅ def, whose equal angles at c and f then give AA similarity
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3), ("d", 10, 0), ("e", 14, 0), ("f", 11, 3)]);
+    add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+    add(&mut db, "eqangle", &["c", "b", "a", "f", "e", "d"]);
+    add(&mut db, "cong", &["a", "b", "d", "e"]);
+    db.run();
+    assert!(derived_by(&db, "contri_eqangle", "eqangle", &["b", "c", "a", "e", "f", "d"]));
+    assert!(derived_by(&db, "aa_sim", "simtri1", &["a", "b", "c", "d", "e", "f"]));
+}
+
+#[test]
+fn unique_intersection_of_two_lines() {
+    // p and q are each claimed on both diagonals of a square
+    let mut square = db(&[("a", 0, 0), ("b", 4, 4), ("c", 0, 4), ("d", 4, 0), ("p", 2, 2), ("q", 2, 2)]);
+    for x in ["p", "q"] {
+        add(&mut square, "col", &[x, "a", "b"]);
+        add(&mut square, "col", &[x, "c", "d"]);
+    }
+    square.run();
+    assert!(derived_by(&square, "unique_intersection", "eq", &["p", "q"]));
+    assert!(square.has_eq("q".into(), "p".into()));
+
+    // ab and cd are one line, which p and q both lie on without coinciding
+    let mut one_line = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 0), ("d", 3, 0), ("p", 2, 0), ("q", 5, 0)]);
+    for x in ["p", "q"] {
+        add(&mut one_line, "col", &[x, "a", "b"]);
+        add(&mut one_line, "col", &[x, "c", "d"]);
+    }
+    one_line.run();
+    assert!(!derived_by(&one_line, "unique_intersection", "eq", &["p", "q"]));
+}
+
+#[test]
+fn contri_simtri_names_the_contri_parent() {
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 4, 0), ("c", 1, 3),
+        ("d", 10, 0), ("e", 14, 0), ("f", 11, 3),
+        ("g", 24, 0), ("h", 20, 0), ("i", 23, 3),
+    ]);
+    add(&mut db, "contri1", &["a", "b", "c", "d", "e", "f"]);
+    add(&mut db, "contri2", &["a", "b", "c", "g", "h", "i"]);
+    db.run();
+
+    let (.., derivations) = db.get_simtri1().into_iter()
+        .find(|(a, b, c, d, e, f, _)| [a, b, c, d, e, f] == ["a", "b", "c", "d", "e", "f"])
+        .unwrap();
+    assert!(derivations.contains(&("contri_simtri".to_string(), vec![fact_id("contri1", ["a", "b", "c", "d", "e", "f"])])));
+
+    let (.., derivations) = db.get_simtri2().into_iter()
+        .find(|(a, b, c, d, e, f, _)| [a, b, c, d, e, f] == ["a", "b", "c", "g", "h", "i"])
+        .unwrap();
+    assert!(derivations.contains(&("contri_simtri".to_string(), vec![fact_id("contri2", ["a", "b", "c", "g", "h", "i"])])));
+}
+
+#[test]
+fn rectangle_diagonals_are_equal() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 4, 3), ("d", 0, 3)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["a", "d", "b", "c"]);
+    add(&mut db, "perp", &["a", "b", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "rectangle_diagonals", "cong", &["a", "c", "b", "d"]));
+}
+
+#[test]
+fn eqangle_cyclic_from_equal_inscribed_angles() {
+    // c and d on the same side of ab see it under the same angle
+    let mut db = db(&[("a", -5, 0), ("b", 5, 0), ("c", 3, 4), ("d", -4, 3)]);
+    add(&mut db, "eqangle", &["a", "c", "b", "a", "d", "b"]);
+    db.run();
+    assert!(derived_by(&db, "eqangle_cyclic", "cyclic", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn rectangle_is_cyclic() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 4, 3), ("d", 0, 3)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["a", "d", "b", "c"]);
+    add(&mut db, "perp", &["a", "b", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "rectangle_cyclic", "cyclic", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn cyclic_eqangle_on_a_square() {
+    let mut db = db(&[("a", 0, 0), ("b", 2, 0), ("c", 2, 2), ("d", 0, 2)]);
+    add(&mut db, "cyclic", &["a", "b", "c", "d"]);
+    db.run();
+    // Chord ab seen from c and d, chord ac seen from b and d, chord bd seen from a and c
+    assert!(derived_by(&db, "cyclic_eqangle", "eqangle", &["a", "c", "b", "a", "d", "b"]));
+    assert!(derived_by(&db, "cyclic_eqangle", "eqangle", &["a", "b", "c", "a", "d", "c"]));
+    assert!(derived_by(&db, "cyclic_eqangle", "eqangle", &["b", "a", "d", "b", "c", "d"]));
+}
+
+#[test]
+fn third_altitude_through_the_orthocenter() {
+    // Altitudes ad and be of abc meet at h
+    let mut db = db(&[("a", 0, 6), ("b", -2, 0), ("c", 6, 0), ("d", 0, 0), ("e", 2, 4), ("h", 0, 2)]);
+    add(&mut db, "perp", &["a", "d", "b", "c"]);
+    add(&mut db, "perp", &["b", "e", "a", "c"]);
+    add(&mut db, "col", &["a", "d", "h"]);
+    add(&mut db, "col", &["b", "e", "h"]);
+    db.run();
+    assert!(derived_by(&db, "third_altitude", "perp", &["c", "h", "a", "b"]));
+}
+
+#[test]
+fn col_trans_closes_four_collinear_points() {
+    let mut db = db(&[("a", 0, 0), ("b", 1, 0), ("c", 2, 0), ("d", 3, 0)]);
+    add(&mut db, "col", &["a", "b", "c"]);
+    add(&mut db, "col", &["a", "b", "d"]);
+    db.run();
+    assert!(derived_by(&db, "col_trans", "col", &["b", "c", "d"]));
+
+    let cols: HashSet<[String; 3]> = db.get_col().into_iter().map(|(a, b, c, _)| [a, b, c]).collect();
+    let names = ["a", "b", "c", "d"];
+    for x in names {
+        for y in names {
+            for z in names {
+                if x != y && x != z && y != z {
+                    assert!(cols.contains(&[x, y, z].map(String::from)));
+                }
+            }
+        }
+    }
+}
+
+// Secants pab and pcd from p outside the circle of radius 5 centered at the origin
+fn two_secants() -> DeductiveDatabase {
+    let mut db = db(&[("p", -6, 3), ("a", -5, 0), ("b", -4, -3), ("c", -4, 3), ("d", 4, 3)]);
+    add(&mut db, "cyclic", &["a", "b", "c", "d"]);
+    add(&mut db, "col", &["p", "a", "b"]);
+    add(&mut db, "col", &["p", "c", "d"]);
+    db
+}
+
+#[test]
+fn power_of_an_external_point() {
+    let mut db = two_secants();
+    db.run();
+    // pa·pb = pc·pd = 20
+    assert!(derived_by(&db, "power_external_point", "eqratio", &["p", "a", "p", "d", "p", "c", "p", "b"]));
+}
+
+#[test]
+fn parallelogram_cong_on_a_unit_square() {
+    let mut db = db(&[("a", 0, 0), ("b", 1, 0), ("c", 1, 1), ("d", 0, 1)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["a", "d", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "parallelogram_cong", "cong", &["a", "b", "c", "d"]));
+    assert!(derived_by(&db, "parallelogram_cong", "cong", &["a", "d", "b", "c"]));
+}
+
+#[test]
+fn secant_triangles_are_similar() {
+    let mut db = two_secants();
+    db.run();
+    assert!(derived_by(&db, "secant_triangles_sim", "simtri2", &["p", "a", "d", "p", "c", "b"]));
+}
+
+#[test]
+fn parallelogram_diag_midp_from_diagonal_cols() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 6, 2), ("d", 2, 2), ("m", 3, 1)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["a", "d", "b", "c"]);
+    add(&mut db, "col", &["a", "m", "c"]);
+    add(&mut db, "col", &["b", "m", "d"]);
+    db.run();
+    assert!(derived_by(&db, "parallelogram_diag_midp", "midp", &["m", "a", "c"]));
+    assert!(derived_by(&db, "parallelogram_diag_midp", "midp", &["m", "b", "d"]));
+}
+
+#[test]
+fn isogonal_cevian_when_enabled() {
+    // ad bisects ∠bac, and the cevians ae and af are reflections of each other over it
+    let mut db = db(&[("a", 0, 4), ("b", -4, 0), ("c", 4, 0), ("d", 0, 0), ("e", -1, 0), ("f", 1, 0)]);
+    add(&mut db, "eqangle", &["b", "a", "d", "d", "a", "c"]);
+    add(&mut db, "eqangle", &["d", "a", "e", "f", "a", "d"]);
+    add(&mut db, "col", &["e", "b", "c"]);
+    add(&mut db, "col", &["f", "b", "c"]);
+    db.run();
+    assert!(!derived_by(&db, "isogonal_cevian", "eqangle", &["b", "a", "e", "f", "a", "c"]));
+
+    db.enable_rule("isogonal_cevian".to_string());
+    db.run();
+    assert!(derived_by(&db, "isogonal_cevian", "eqangle", &["b", "a", "e", "f", "a", "c"]));
+}
+
+#[test]
+fn lconst_round_trip_and_cong() {
+    let mut db = db(&[("a", 0, 0), ("b", 3, 0), ("c", 0, 2), ("d", 3, 2)]);
+    add(&mut db, "lconst", &["a", "b", "3", "2"]);
+    add(&mut db, "lconst", &["c", "d", "6", "4"]);
+    db.run();
+    let lconsts: Vec<(String, String, i32, i32)> = db.get_lconst().into_iter().map(|(a, b, m, n, _)| (a, b, m, n)).collect();
+    assert!(lconsts.contains(&("a".to_string(), "b".to_string(), 3, 2)));
+    assert!(lconsts.contains(&("b".to_string(), "a".to_string(), 3, 2)));
+    assert!(derived_by(&db, "lconst_cong", "cong", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn newton_gauss_line_of_a_quadrilateral() {
+    // Opposite sides of abcd meet at e (ab, cd) and f (ad, bc)
+    let mut db = db(&[
+        ("a", 4, 0), ("b", 8, 0), ("c", 0, 8), ("d", 0, 12), ("e", 0, 0), ("f", 2, 6),
+        ("m", 2, 4), ("n", 4, 6), ("k", 1, 3),
+    ]);
+    add(&mut db, "col", &["e", "a", "b"]);
+    add(&mut db, "col", &["e", "c", "d"]);
+    add(&mut db, "col", &["f", "a", "d"]);
+    add(&mut db, "col", &["f", "b", "c"]);
+    add(&mut db, "midp", &["m", "a", "c"]);
+    add(&mut db, "midp", &["n", "b", "d"]);
+    add(&mut db, "midp", &["k", "e", "f"]);
+    db.run();
+    assert!(derived_by(&db, "diagonal_midpoints_col", "col", &["m", "n", "k"]));
+}
+
+#[test]
+fn rconst_inversion_eqratio_and_cong() {
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 2, 0), ("c", 0, 3), ("d", 3, 3),
+        ("e", 0, 5), ("f", 4, 5), ("g", 0, 7), ("h", 6, 7),
+    ]);
+    add(&mut db, "rconst", &["a", "b", "c", "d", "2", "3"]);
+    add(&mut db, "rconst", &["e", "f", "g", "h", "4", "6"]);
+    add(&mut db, "rconst", &["a", "b", "e", "g", "1", "1"]);
+    db.run();
+    let rconsts: Vec<[String; 4]> = db.get_rconst().into_iter()
+        .filter(|(.., m, n, _)| (*m, *n) == (3, 2))
+        .map(|(a, b, c, d, ..)| [a, b, c, d])
+        .collect();
+    assert!(rconsts.contains(&["c", "d", "a", "b"].map(String::from)));
+    assert!(derived_by(&db, "rconst_eqratio", "eqratio", &["a", "b", "c", "d", "e", "f", "g", "h"]));
+    assert!(derived_by(&db, "rconst_cong", "cong", &["a", "b", "e", "g"]));
+}
+
+#[test]
+fn reflection_eqangle_over_a_line() {
+    // a2b2c2 is abc reflected over the line uv
+    let mut db = db(&[
+        ("u", 0, 0), ("v", 0, 4),
+        ("a", 1, 1), ("b", 3, 0), ("c", 2, 3),
+        ("a2", -1, 1), ("b2", -3, 0), ("c2", -2, 3),
+    ]);
+    for (x, x2) in [("a", "a2"), ("b", "b2"), ("c", "c2")] {
+        add(&mut db, "cong", &["u", x, "u", x2]);
+        add(&mut db, "cong", &["v", x, "v", x2]);
+    }
+    db.run();
+    assert!(derived_by(&db, "reflection_eqangle", "eqangle", &["a", "b", "c", "c2", "b2", "a2"]));
+}
+
+#[test]
+fn reflection_eqangle_through_a_point() {
+    // a3b3c3 is abc reflected through o
+    let mut db = db(&[
+        ("o", 5, 5),
+        ("a", 1, 1), ("b", 3, 0), ("c", 2, 3),
+        ("a3", 9, 9), ("b3", 7, 10), ("c3", 8, 7),
+    ]);
+    for (x, x3) in [("a", "a3"), ("b", "b3"), ("c", "c3")] {
+        add(&mut db, "midp", &["o", x, x3]);
+    }
+    db.run();
+    assert!(derived_by(&db, "reflection_eqangle", "eqangle", &["a", "b", "c", "a3", "b3", "c3"]));
+}
+
+#[test]
+fn aconst_add_feeds_a_right_angle() {
+    // 30° + 60° = 90°
+    let mut db = db(&[("a", 4, 0), ("b", 0, 0), ("c", 3, 2), ("d", 0, 4)]);
+    add(&mut db, "aconst", &["a", "b", "c", "1", "6"]);
+    add(&mut db, "aconst", &["c", "b", "d", "1", "3"]);
+    db.run();
+    assert!(derived_by(&db, "aconst_add", "aconst", &["a", "b", "d", "1", "2"]));
+    assert!(derived_by(&db, "aconst_perp", "perp", &["b", "a", "b", "d"]));
+}
+
+#[test]
+fn circumcenter_perp_foot_is_the_midpoint() {
+    let mut db = db(&[("o", 0, 0), ("b", -3, -4), ("c", 3, -4), ("m", 0, -4)]);
+    add(&mut db, "cong", &["o", "b", "o", "c"]);
+    add(&mut db, "perp", &["o", "m", "b", "c"]);
+    add(&mut db, "col", &["b", "m", "c"]);
+    db.run();
+    assert!(derived_by(&db, "circumcenter_perp_midpoint", "midp", &["m", "b", "c"]));
+    assert!(derived_by(&db, "circumcenter_perp_midpoint", "cong", &["b", "m", "m", "c"]));
+}
+
+#[test]
+fn aconst_perp_in_both_directions() {
+    let mut db = db(&[("a", 4, 0), ("b", 0, 0), ("c", 0, 4), ("d", 10, 0), ("e", 14, 0), ("f", 10, 4)]);
+    add(&mut db, "aconst", &["a", "b", "c", "3", "2"]);
+    add(&mut db, "perp", &["e", "d", "d", "f"]);
+    db.run();
+    assert!(derived_by(&db, "aconst_perp", "perp", &["b", "a", "b", "c"]));
+    assert!(derived_by(&db, "aconst_perp", "aconst", &["e", "d", "f", "1", "2"]));
+}
+
+#[test]
+fn medial_triangle_is_similar_with_the_same_orientation() {
+    // x, y, z are the midpoints of bc, ca, ab
+    let mut db = db(&[("a", 0, 0), ("b", 8, 0), ("c", 2, 6), ("x", 5, 3), ("y", 1, 3), ("z", 4, 0)]);
+    add(&mut db, "midp", &["x", "b", "c"]);
+    add(&mut db, "midp", &["y", "c", "a"]);
+    add(&mut db, "midp", &["z", "a", "b"]);
+    db.run();
+    assert!(derived_by(&db, "medial_triangle_sim", "simtri1", &["x", "y", "z", "a", "b", "c"]));
+    assert!(!derived_by(&db, "medial_triangle_sim", "simtri2", &["x", "y", "z", "a", "b", "c"]));
+    let coords = |names: [&str; 3]| names.map(|n| {
+        let (x, y, _) = db.points.iter().find(|(_, _, name)| name == n).unwrap();
+        (*x, *y)
+    }).to_vec();
+    assert_eq!(same_orientation(coords(["x", "y", "z"]), coords(["a", "b", "c"]), 0.0), Some(true));
+}
+
+#[test]
+fn eqratio_trans_chains_three_ratios() {
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 1, 0), ("c", 0, 1), ("d", 2, 1),
+        ("e", 0, 2), ("f", 3, 2), ("g", 0, 3), ("h", 6, 3),
+        ("i", 0, 4), ("j", 4, 4), ("k", 0, 5), ("l", 8, 5),
+    ]);
+    add(&mut db, "eqratio", &["a", "b", "c", "d", "e", "f", "g", "h"]);
+    add(&mut db, "eqratio", &["e", "f", "g", "h", "i", "j", "k", "l"]);
+    db.run();
+    assert!(derived_by(&db, "eqratio_trans", "eqratio", &["a", "b", "c", "d", "i", "j", "k", "l"]));
+}
+
+#[test]
+fn parallelogram_diagonals_share_a_midpoint() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 6, 2), ("d", 2, 2), ("m", 3, 1)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["a", "d", "b", "c"]);
+    add(&mut db, "midp", &["m", "a", "c"]);
+    db.run();
+    assert!(derived_by(&db, "parallelogram_diagonals", "midp", &["m", "b", "d"]));
+    assert!(derived_by(&db, "parallelogram_diagonals", "cong", &["a", "m", "m", "c"]));
+    assert!(derived_by(&db, "parallelogram_diagonals", "cong", &["b", "m", "m", "d"]));
+}
+
+#[test]
+fn sas_sim_from_ratio_and_included_angle() {
+    // def is abc scaled by 2
+    let mut db = db(&[("a", 0, 0), ("b", 2, 0), ("c", 0, 1), ("d", 10, 0), ("e", 14, 0), ("f", 10, 2)]);
+    add(&mut db, "eqratio", &["a", "b", "d", "e", "a", "c", "d", "f"]);
+    add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+    db.run();
+    assert!(derived_by(&db, "sas_sim", "simtri1", &["a", "b", "c", "d", "e", "f"]));
+}
+
+#[test]
+fn tangent_angle_center_with_perpendicular_tangents() {
+    // Tangents pu and pv to the circle centered o meet at a right angle
+    let mut db = db(&[("o", 0, 0), ("u", 5, 0), ("v", 0, 5), ("p", 5, 5)]);
+    add(&mut db, "perp", &["o", "u", "u", "p"]);
+    add(&mut db, "perp", &["o", "v", "v", "p"]);
+    add(&mut db, "cong", &["o", "u", "o", "v"]);
+    add(&mut db, "perp", &["p", "u", "p", "v"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_angle_center", "perp", &["o", "u", "o", "v"]));
+
+    let mut db = self::db(&[("o", 0, 0), ("u", 5, 0), ("v", 0, 5), ("p", 5, 5)]);
+    add(&mut db, "perp", &["o", "u", "u", "p"]);
+    add(&mut db, "perp", &["o", "v", "v", "p"]);
+    add(&mut db, "cong", &["o", "u", "o", "v"]);
+    add(&mut db, "aconst", &["u", "p", "v", "1", "2"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_angle_center", "aconst", &["u", "o", "v", "1", "2"]));
+}
+
+#[test]
+fn sss_sim_with_sides_in_ratio_two() {
+    // def is abc scaled by 2
+    let mut db = db(&[("a", 0, 0), ("b", 2, 0), ("c", 0, 1), ("d", 10, 0), ("e", 14, 0), ("f", 10, 2)]);
+    add(&mut db, "eqratio", &["a", "b", "d", "e", "b", "c", "e", "f"]);
+    add(&mut db, "eqratio", &["b", "c", "e", "f", "a", "c", "d", "f"]);
+    db.run();
+    assert!(derived_by(&db, "sss_sim", "simtri1", &["a", "b", "c", "d", "e", "f"]));
+}
+
+#[test]
+fn pedal_collinear_only_when_enabled() {
+    // p is on the circumcircle of abc, so the feet x, y, z lie on its Simson line
+    let mut db = db(&[
+        ("a", -5, 0), ("b", -4, -3), ("c", -4, 3), ("p", 0, -5),
+        ("x", -4, -5), ("y", -6, -3), ("z", -3, -6),
+    ]);
+    add(&mut db, "perp", &["p", "x", "b", "c"]);
+    add(&mut db, "col", &["x", "b", "c"]);
+    add(&mut db, "perp", &["p", "y", "c", "a"]);
+    add(&mut db, "col", &["y", "c", "a"]);
+    add(&mut db, "perp", &["p", "z", "a", "b"]);
+    add(&mut db, "col", &["z", "a", "b"]);
+    db.run();
+    assert!(!derived_by(&db, "pedal_collinear", "col", &["x", "y", "z"]));
+
+    db.enable_rule("pedal_collinear".to_string());
+    db.run();
+    assert!(derived_by(&db, "pedal_collinear", "col", &["x", "y", "z"]));
+}
+
+#[test]
+fn has_matches_goals_up_to_symmetry() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 3), ("d", 4, 3), ("e", -4, 3)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "lconst", &["a", "b", "4", "1"]);
+    add(&mut db, "eqratio", &["a", "b", "c", "d", "a", "c", "b", "d"]);
+    add(&mut db, "tangent", &["e", "d", "a", "c"]);
+    db.run();
+    assert!(db.has_para("d".into(), "c".into(), "b".into(), "a".into()));
+    assert!(!db.has_para("a".into(), "c".into(), "b".into(), "d".into()));
+    assert!(db.has_lconst("b".into(), "a".into(), 4, 1));
+    assert!(!db.has_lconst("a".into(), "b".into(), 3, 1));
+    assert!(db.has_eqratio(
+        "c".into(), "d".into(), "a".into(), "b".into(),
+        "b".into(), "d".into(), "a".into(), "c".into()
+    ));
+    assert!(db.has_tangent("d".into(), "e".into(), "a".into(), "c".into()));
+    assert!(!db.has_tangent("d".into(), "e".into(), "b".into(), "c".into()));
+}
+
+#[test]
+fn chord_bisection_ratio_states_the_unit_ratio() {
+    let mut db = db(&[("o", 0, 0), ("a", -4, 3), ("b", 4, 3), ("m", 0, 3)]);
+    add(&mut db, "cong", &["o", "a", "o", "b"]);
+    add(&mut db, "perp", &["o", "m", "a", "b"]);
+    add(&mut db, "col", &["a", "m", "b"]);
+    db.run();
+    assert!(derived_by(&db, "chord_bisection_ratio", "eqratio", &["a", "m", "m", "b", "a", "b", "a", "b"]));
+    assert!(derived_by(&db, "circumcenter_perp_midpoint", "cong", &["a", "m", "m", "b"]));
+    assert!(!derived_by(&db, "chord_bisection_ratio", "cong", &["a", "m", "m", "b"]));
+}
+
+#[test]
+fn explain_lists_every_derivation_of_a_goal() {
+    let mut db = db(&[("o", 0, 0), ("a", -4, 3), ("b", 4, 3), ("m", 0, 3)]);
+    add(&mut db, "cong", &["o", "a", "o", "b"]);
+    add(&mut db, "perp", &["o", "m", "a", "b"]);
+    add(&mut db, "col", &["a", "m", "b"]);
+    db.run();
+
+    // am = mb follows from the perpendicular from o and again from the midpoint it gives,
+    // each in its own orientation, and every orientation lists the others as symmetries
+    let rules = |[a, b, c, d]: [&str; 4]| -> Vec<String> {
+        db.explain_cong(a.into(), b.into(), c.into(), d.into())
+            .unwrap().into_iter().map(|(rule, _)| rule).collect()
+    };
+    assert!(rules(["a", "m", "m", "b"]).contains(&"circumcenter_perp_midpoint".to_string()));
+    assert!(rules(["a", "m", "b", "m"]).contains(&"midp_cong".to_string()));
+    assert!(rules(["a", "m", "b", "m"]).contains(&"sym".to_string()));
+
+    let derivations = db.explain_eqratio(
+        "a".into(), "m".into(), "m".into(), "b".into(),
+        "a".into(), "b".into(), "a".into(), "b".into()
+    ).unwrap();
+    assert!(derivations.iter().any(|(rule, _)| rule == "chord_bisection_ratio"));
+    assert_eq!(db.explain_tangent("a".into(), "b".into(), "o".into(), "m".into()), None);
+}
+
+#[test]
+fn tangent_and_secant_triangles_are_similar() {
+    // tp is tangent at p to the circle centered o and the secant from t meets it at a and c
+    let mut db = db(&[("o", 0, 0), ("p", 5, 0), ("t", 5, 5), ("a", 4, 3), ("c", 0, -5)]);
+    add(&mut db, "perp", &["o", "p", "p", "t"]);
+    add(&mut db, "cong", &["o", "p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "c"]);
+    add(&mut db, "eqangle", &["t", "p", "a", "p", "c", "a"]);
+    add(&mut db, "col", &["t", "a", "c"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_inscribed_sim", "simtri2", &["t", "p", "a", "t", "c", "p"]));
+}
+
+#[test]
+fn proof_tree_reaches_the_axioms() {
+    // ab ∥ gh needs two steps through the parallel lines in between
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 4, 0), ("c", 0, 1), ("d", 4, 1),
+        ("e", 0, 2), ("f", 4, 2), ("g", 0, 3), ("h", 4, 3),
+    ]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    add(&mut db, "para", &["e", "f", "g", "h"]);
+    db.run();
+
+    // Height of a proof tree, checking that its leaves are axioms
+    fn height(node: &Bound<'_, PyAny>) -> usize {
+        let premises = node.get_item("premises").unwrap();
+        let premises = premises.downcast::<PyList>().unwrap();
+        if premises.is_empty() {
+            assert_eq!(node.get_item("rule").unwrap().extract::<String>().unwrap(), "axiom");
+            return 1;
+        }
+        1 + premises.iter().map(|p| height(&p)).max().unwrap()
+    }
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let tree = db.proof_tree(py, fact_id("para", ["a", "b", "g", "h"])).unwrap().unwrap();
+        let tree = tree.bind(py);
+        assert_eq!(tree.get_item("fact").unwrap().extract::<String>().unwrap(), fact_id("para", ["a", "b", "g", "h"]));
+        assert!(height(tree) >= 3);
+
+        assert!(db.proof_tree(py, fact_id("perp", ["a", "b", "g", "h"])).unwrap().is_none());
+    });
+}
+
+#[test]
+fn diameter_subtends_right_angles() {
+    let mut db = db(&[("o", 0, 0), ("a", -5, 0), ("b", 5, 0), ("p", 3, 4), ("q", -4, -3)]);
+    db.add_diameter("o".into(), "a".into(), "b".into());
+    add(&mut db, "cyclic", &["a", "p", "b", "q"]);
+    db.run();
+    // Nothing states that the circle through a, p, b, q is the one centered o
+    assert!(!derived_by(&db, "diameter_right_angle", "perp", &["p", "a", "p", "b"]));
+
+    add(&mut db, "cong", &["o", "a", "o", "p"]);
+    db.run();
+    assert!(derived_by(&db, "diameter_right_angle", "perp", &["p", "a", "p", "b"]));
+    assert!(derived_by(&db, "diameter_right_angle", "perp", &["q", "a", "q", "b"]));
+}
+
+#[test]
+fn diameter_right_angle_from_a_collinear_equidistant_center() {
+    // o is stated to be the center only through col(b, o, d) and the equal radii
+    let mut db = db(&[("o", 0, 0), ("b", -5, 0), ("d", 5, 0), ("r", 3, 4), ("y", -4, -3)]);
+    add(&mut db, "cyclic", &["b", "r", "y", "d"]);
+    add(&mut db, "col", &["b", "o", "d"]);
+    add(&mut db, "cong", &["b", "o", "r", "o"]);
+    add(&mut db, "cong", &["r", "o", "d", "o"]);
+    db.run();
+    assert!(derived_by(&db, "col_cong_midp", "midp", &["o", "b", "d"]));
+    assert!(derived_by(&db, "diameter_right_angle", "perp", &["r", "b", "r", "d"]));
+}
+
+#[test]
+fn col_cong_midp_pairs_on_its_second_endpoint() {
+    // n is derived as the midpoint of ab in name order; the midsegment shares b with m
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 2, 6), ("n", 2, 0), ("m", 3, 3)]);
+    add(&mut db, "col", &["a", "n", "b"]);
+    add(&mut db, "cong", &["n", "a", "n", "b"]);
+    add(&mut db, "midp", &["m", "b", "c"]);
+    db.run();
+    assert!(derived_by(&db, "col_cong_midp", "midp", &["n", "a", "b"]));
+    assert!(derived_by(&db, "midsegment", "para", &["n", "m", "a", "c"]));
+}
+
+#[test]
+fn shortest_proof_takes_the_one_step_route() {
+    // ab ∥ gh follows in one step through xy, and in more through cd and ef
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 4, 0), ("c", 0, 1), ("d", 4, 1), ("e", 0, 2), ("f", 4, 2),
+        ("g", 0, 3), ("h", 4, 3), ("x", 0, 5), ("y", 4, 5),
+    ]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    add(&mut db, "para", &["e", "f", "g", "h"]);
+    add(&mut db, "para", &["a", "b", "x", "y"]);
+    add(&mut db, "para", &["g", "h", "x", "y"]);
+    db.run();
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let goal = fact_id("para", ["a", "b", "g", "h"]);
+        let chain = db.shortest_proof(py, goal.clone()).unwrap().unwrap();
+        let chain = chain.bind(py).downcast::<PyList>().unwrap().clone();
+        assert_eq!(chain.len(), 3);
+
+        let last = chain.get_item(2).unwrap();
+        assert_eq!(last.get_item("fact").unwrap().extract::<String>().unwrap(), goal);
+        assert_eq!(last.get_item("depth").unwrap().extract::<usize>().unwrap(), 1);
+        let mut premises: Vec<String> = last.get_item("premises").unwrap().extract().unwrap();
+        premises.sort();
+        let mut expected = vec![fact_id("para", ["a", "b", "x", "y"]), fact_id("para", ["g", "h", "x", "y"])];
+        expected.sort();
+        assert_eq!(premises, expected);
+    });
+}
+
+#[test]
+fn derivations_per_fact_stay_under_the_cap() {
+    // Every pair of five horizontal lines is given as parallel
+    let lines = [("a", "b"), ("c", "d"), ("e", "f"), ("g", "h"), ("i", "j")];
+    let build = || {
+        let mut db = db(&[
+            ("a", 0, 0), ("b", 4, 0), ("c", 0, 1), ("d", 4, 1), ("e", 0, 2),
+            ("f", 4, 2), ("g", 0, 3), ("h", 4, 3), ("i", 0, 4), ("j", 4, 4),
+        ]);
+        for (i, (a, b)) in lines.iter().enumerate() {
+            for (c, d) in &lines[i + 1..] {
+                add(&mut db, "para", &[a, b, c, d]);
+            }
+        }
+        db
+    };
+
+    let mut db = build();
+    db.run();
+    assert!(db.get_para().iter().any(|(.., derivations)| derivations.len() > 2));
+
+    let mut db = build();
+    db.set_max_derivations_per_fact(Some(2)).unwrap();
+    db.run();
+    assert!(db.get_para().iter().all(|(.., derivations)| (1..=2).contains(&derivations.len())));
+    assert!(db.set_max_derivations_per_fact(Some(0)).is_err());
+
+    // A single derivation per fact is the shortest one, so input facts keep their axiom
+    let mut db = build();
+    db.set_max_derivations_per_fact(Some(1)).unwrap();
+    db.run();
+    for (a, b, c, d, derivations) in db.get_para() {
+        if db.para_facts.contains(&(a, b, c, d)) {
+            assert_eq!(derivations, vec![("axiom".to_string(), vec![])]);
+        }
+    }
+}
+
+#[test]
+fn full_provenance_keeps_the_shorter_derivations() {
+    let settings = ProvenanceSettings { track: true, max_derivations: Some(2) };
+    let parents = |n: usize| move || (0..n).map(|i| format!("p{}", i)).collect::<Vec<_>>();
+    let mut prov = settings.derive("two", parents(2));
+    assert!(prov.meet_mut(settings.derive("three", parents(3))));
+
+    // Full: a derivation with more premises is dropped, one with fewer replaces the widest
+    assert!(!prov.meet_mut(settings.derive("four", parents(4))));
+    assert!(!prov.meet_mut(settings.derive("three", parents(3))));
+    assert!(prov.meet_mut(settings.axiom()));
+    let rules: Vec<&str> = prov.derivations.iter().map(|d| d.rule.as_str()).collect();
+    assert_eq!(rules, ["axiom", "two"]);
+}
+
+#[test]
+fn repeated_input_facts_are_seeded_once() {
+    let mut db = db(&[("a", 0, 0), ("b", 1, 1), ("c", 2, 2)]);
+    for _ in 0..3 {
+        add(&mut db, "col", &["a", "b", "c"]);
+    }
+    add(&mut db, "col", &["c", "b", "a"]);
+    db.add_cols(vec![("a".into(), "b".into(), "c".into()), ("b".into(), "a".into(), "c".into())]);
+    assert_eq!(db.input_facts().len(), 1);
+
+    // Also after a reload, which rebuilds the ids
+    let mut reloaded = DeductiveDatabase::from_json(db.to_json().unwrap()).unwrap();
+    add(&mut reloaded, "col", &["b", "c", "a"]);
+    assert_eq!(reloaded.input_facts().len(), 1);
+
+    db.run();
+    let (.., derivations) = db.get_col().into_iter()
+        .find(|(a, b, c, _)| [a, b, c] == ["a", "b", "c"])
+        .unwrap();
+    assert_eq!(derivations.iter().filter(|(rule, _)| rule == "axiom").count(), 1);
+}
+
+#[test]
+fn validate_reports_facts_with_unknown_points() {
+    let mut db = db(&[("a", 0, 0), ("b", 1, 1), ("c", 2, 2)]);
+    add(&mut db, "col", &["a", "b", "c"]);
+    assert!(db.validate().is_empty());
+
+    add(&mut db, "para", &["a", "b", "c", "z"]);
+    assert_eq!(db.validate(), vec![fact_id("para", ["a", "b", "c", "z"])]);
+}
+
+#[test]
+fn run_incremental_takes_fewer_iterations_for_a_new_fact() {
+    // A chain of parallel lines takes several iterations to close, a collinear triple one
+    let mut db = db(&[
+        ("a", 0, 0), ("b", 4, 0), ("c", 0, 1), ("d", 4, 1), ("e", 0, 2), ("f", 4, 2),
+        ("g", 0, 3), ("h", 4, 3), ("i", 0, 4), ("j", 4, 4), ("x", 0, 9), ("y", 1, 9), ("z", 2, 9),
+    ]);
+    for line in [["a", "b", "c", "d"], ["c", "d", "e", "f"], ["e", "f", "g", "h"], ["g", "h", "i", "j"]] {
+        add(&mut db, "para", &line);
+    }
+    db.run_incremental();
+    add(&mut db, "col", &["x", "y", "z"]);
+    db.run_incremental();
+    let incremental = db.iterations;
+
+    let mut full = db.clone();
+    full.run();
+    assert_eq!(fact_set_hash(&db), fact_set_hash(&full));
+    assert!(incremental < full.iterations, "{} incremental, {} full", incremental, full.iterations);
+}
+
+#[test]
+fn run_incremental_matches_a_full_run() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    db.run_incremental();
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    db.run_incremental();
+    assert!(db.has_para("a".into(), "b".into(), "e".into(), "f".into()));
+    let full = |db: &DeductiveDatabase| {
+        let mut full = db.clone();
+        full.run();
+        fact_set_hash(&full)
+    };
+    assert_eq!(fact_set_hash(&db), full(&db));
+
+    // A new iteration limit or new coordinate facts start over
+    db.set_max_iterations(Some(1)).unwrap();
+    db.run_incremental();
+    db.set_max_iterations(None).unwrap();
+    db.run_incremental();
+    assert!(db.saturated());
+    assert_eq!(fact_set_hash(&db), full(&db));
+
+    db.infer_from_coordinates().unwrap();
+    db.run_incremental();
+    assert!(db.has_perp("a".into(), "c".into(), "a".into(), "b".into()));
+    assert_eq!(fact_set_hash(&db), full(&db));
+}
+
+#[test]
+fn clear_leaves_no_stale_facts() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    db.run();
+
+    db.clear_derived();
+    assert!(db.get_para().is_empty());
+    db.run();
+    assert!(db.has_para("a".into(), "b".into(), "c".into(), "d".into()));
+
+    db.clear();
+    for (name, x, y) in [("p", 0, 0), ("q", 1, 1), ("r", 2, 2)] {
+        db.add_point(Coord::Int(x), Coord::Int(y), name.to_string()).unwrap();
+    }
+    add(&mut db, "col", &["p", "q", "r"]);
+    db.run_incremental();
+    assert_eq!(db.get_points().len(), 3);
+    assert!(db.get_para().iter().all(|(a, ..)| ["p", "q", "r"].contains(&a.as_str())));
+    assert!(!db.has_para("a".into(), "b".into(), "c".into(), "d".into()));
+    assert!(db.has_col("r".into(), "q".into(), "p".into()));
+}
+
+#[test]
+fn symmetric_descriptions_share_a_fact_id() {
+    assert_eq!(fact_id("col", ["c", "a", "b"]), fact_id("col", ["a", "b", "c"]));
+    assert_eq!(fact_id("cong", ["d", "c", "b", "a"]), "cong(a,b,c,d)");
+    assert_eq!(fact_id("eqangle", ["f", "e", "d", "c", "b", "a"]), fact_id("eqangle", ["a", "b", "c", "d", "e", "f"]));
+    assert_eq!(fact_id("midp", ["m", "b", "a"]), "midp(m,a,b)");
+    assert_ne!(fact_id("midp", ["a", "m", "b"]), fact_id("midp", ["m", "a", "b"]));
+    assert_eq!(canonical_fact_id("cong(B, A, C, D)"), "cong(A,B,C,D)");
+    assert_eq!(canonical_fact_id("not a fact"), "not a fact");
+}
+
+#[test]
+fn json_round_trip_keeps_the_solved_database() {
+    let mut db = db(&[("a", 0, 0), ("b", 5_000_000_000, 0), ("c", 5_000_000_000, 1), ("d", 10_000_000_000, 0)]);
+    add(&mut db, "col", &["a", "b", "d"]);
+    add(&mut db, "aconst", &["a", "b", "c", "1", "2"]);
+    db.run();
+
+    let loaded = DeductiveDatabase::from_json(db.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.get_col(), db.get_col());
+    assert_eq!(loaded.get_aconst(), db.get_aconst());
+    assert!(matches!(loaded.get_points()[3].0, Coord::Int(10_000_000_000)));
+    assert!(DeductiveDatabase::from_json("{".to_string()).is_err());
+}
+
+#[test]
+fn to_dot_draws_a_two_step_proof() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    db.run();
+
+    let dot = db.to_dot();
+    assert!(dot.starts_with("digraph proof {"));
+    let axiom = fact_id("para", ["a", "b", "c", "d"]);
+    let goal = fact_id("para", ["a", "b", "e", "f"]);
+    assert!(dot.contains(&format!("{:?} [shape=box, style=filled, fillcolor=lightgrey];", axiom)));
+    assert!(dot.contains(&format!("{:?} [shape=box];", goal)));
+    assert!(dot.contains("[label=\"common_parallel\", shape=ellipse, fontsize=10];"));
+    assert!(dot.lines().any(|line| line.starts_with(&format!("    {:?} -> r", axiom))));
+    assert!(dot.lines().any(|line| line.ends_with(&format!("-> {:?};", goal))));
+}
+
+#[test]
+fn bulk_and_single_adds_build_the_same_database() {
+    let points = [("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2)];
+    let mut single = db(&points);
+    add(&mut single, "para", &["a", "b", "c", "d"]);
+    add(&mut single, "perp", &["a", "b", "a", "c"]);
+    add(&mut single, "col", &["a", "b", "c"]);
+
+    let mut bulk = DeductiveDatabase::default();
+    bulk.add_points(points.iter().map(|(name, x, y)| (Coord::Int(*x), Coord::Int(*y), name.to_string())).collect()).unwrap();
+    bulk.add_paras(vec![("a".into(), "b".into(), "c".into(), "d".into()), ("a".into(), "b".into(), "c".into(), "d".into())]);
+    bulk.add_perps(vec![("a".into(), "b".into(), "a".into(), "c".into())]);
+    bulk.add_cols(vec![("a".into(), "b".into(), "c".into())]);
+    assert_eq!(bulk.to_json().unwrap(), single.to_json().unwrap());
+}
+
+#[test]
+fn same_orientation_of_degenerate_triangles() {
+    let pts = |l: &[(i64, i64)]| l.iter().map(|&(x, y)| (Coord::Int(x), Coord::Int(y))).collect::<Vec<_>>();
+    let ccw = pts(&[(0, 0), (4, 0), (0, 3)]);
+    let cw = pts(&[(0, 0), (0, 3), (4, 0)]);
+    assert_eq!(same_orientation(ccw.clone(), ccw.clone(), 0.0), Some(true));
+    assert_eq!(same_orientation(ccw.clone(), cw.clone(), 0.0), Some(false));
+
+    // Strictly collinear, and with a repeated vertex
+    assert_eq!(same_orientation(pts(&[(0, 0), (1, 1), (3, 3)]), ccw.clone(), 0.0), None);
+    assert_eq!(same_orientation(cw.clone(), pts(&[(0, 0), (4, 0), (0, 0)]), 0.0), None);
+
+    // Off the line by one unit over a long base: thin but exactly nondegenerate
+    let thin = pts(&[(0, 0), (200_000, 1), (400_000, 1)]);
+    assert!(is_nondegenerate(thin.clone(), 0.0));
+    assert_eq!(same_orientation(thin, cw, 0.0), Some(true));
+}
+
+#[test]
+fn is_between_far_apart_coordinates() {
+    let pt = |x: i64, y: i64| (Coord::Int(x), Coord::Int(y));
+    // The differences overflow i64
+    assert!(is_between(pt(0, 0), pt(i64::MIN, 0), pt(i64::MAX, 0), 0.0));
+    assert!(!is_between(pt(i64::MAX, 0), pt(i64::MIN, 0), pt(0, 0), 0.0));
+}
+
+#[test]
+fn two_midpoints_of_a_segment_are_equal() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("m", 2, 0), ("n", 2, 0)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "midp", &["n", "b", "a"]);
+    db.run();
+    assert!(derived_by(&db, "midp_unique", "eq", &["m", "n"]));
+    assert!(db.has_eq("n".into(), "m".into()));
+}
+
+#[test]
+fn eq_substitution_carries_facts_to_the_equal_point() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("m", 2, 0), ("n", 2, 0), ("c", 2, 3), ("d", 2, 5)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "midp", &["n", "a", "b"]);
+    add(&mut db, "col", &["m", "c", "d"]);
+    db.run();
+    assert!(!db.has_col("n".into(), "c".into(), "d".into()));
+
+    db.enable_rule("eq_substitution".to_string());
+    db.run();
+    assert!(derived_by(&db, "eq_subst", "col", &["n", "c", "d"]));
+}
+
+#[test]
+fn eq_substitution_follows_unique_intersection() {
+    // p and q are both claimed at the crossing of the diagonals of a square, a right angle
+    let mut db = db(&[("a", 0, 0), ("b", 4, 4), ("c", 0, 4), ("d", 4, 0), ("p", 2, 2), ("q", 2, 2)]);
+    for x in ["p", "q"] {
+        add(&mut db, "col", &[x, "a", "b"]);
+        add(&mut db, "col", &[x, "c", "d"]);
+    }
+    add(&mut db, "aconst", &["a", "p", "c", "1", "2"]);
+    db.enable_rule("eq_substitution".to_string());
+    db.run();
+    assert!(derived_by(&db, "unique_intersection", "eq", &["p", "q"]));
+    assert!(derived_by(&db, "eq_subst", "aconst", &["a", "q", "c", "1", "2"]));
+}
+
+#[test]
+fn stats_count_rules_and_relations() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3), ("d", 10, 0), ("e", 14, 0), ("f", 11, 3)]);
+    add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+    add(&mut db, "eqangle", &["c", "b", "a", "f", "e", "d"]);
+    add(&mut db, "cong", &["a", "b", "d", "e"]);
+    db.run();
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let stats = db.stats(py).unwrap();
+        let stats = stats.bind(py);
+        let rules: HashMap<String, usize> = stats.get_item("rules").unwrap().extract().unwrap();
+        let relations: HashMap<String, usize> = stats.get_item("relations").unwrap().extract().unwrap();
+        assert!(rules["asa_cong"] >= 1);
+        assert_eq!(rules["axiom"], 3);
+        assert_eq!(relations["contri1"], db.get_contri1().len());
+    });
+}
+
+// Every pair of eight horizontal lines given as parallel, which takes several iterations
+fn parallel_lines() -> DeductiveDatabase {
+    let names: Vec<(String, String)> = (0..8).map(|i| (format!("a{}", i), format!("b{}", i))).collect();
+    let mut db = DeductiveDatabase::default();
+    for (i, (a, b)) in names.iter().enumerate() {
+        db.add_point(Coord::Int(0), Coord::Int(i as i64), a.clone()).unwrap();
+        db.add_point(Coord::Int(4), Coord::Int(i as i64), b.clone()).unwrap();
+    }
+    for (i, (a, b)) in names.iter().enumerate() {
+        for (c, d) in &names[i + 1..] {
+            db.add_para(a.clone(), b.clone(), c.clone(), d.clone());
+        }
+    }
+    db
+}
+
+#[test]
+fn max_iterations_stops_before_the_fixpoint() {
+    let mut full = parallel_lines();
+    full.run();
+    assert!(full.saturated());
+
+    let mut db = parallel_lines();
+    db.set_max_iterations(Some(1)).unwrap();
+    db.run();
+    assert!(!db.saturated());
+    assert!(!db.get_para().is_empty());
+    assert!(db.get_para().len() < full.get_para().len());
+    assert!(db.set_max_iterations(Some(0)).is_err());
+}
+
+#[test]
+fn run_with_timeout_keeps_partial_results() {
+    let mut db = parallel_lines();
+    assert!(!db.run_with_timeout(0));
+    assert!(!db.saturated());
+    assert!(!db.get_para().is_empty());
+
+    let mut db = parallel_lines();
+    assert!(db.run_with_timeout(600_000));
+    assert!(db.saturated());
+}
+
+#[test]
+fn right_angle_on_the_hypotenuse_circle() {
+    // o is the midpoint of the hypotenuse bd of the right triangle brd
+    let mut db = db(&[("b", -5, 0), ("d", 5, 0), ("r", 3, 4), ("o", 0, 0), ("y", -4, 3)]);
+    add(&mut db, "perp", &["b", "r", "r", "d"]);
+    add(&mut db, "midp", &["o", "b", "d"]);
+    add(&mut db, "cong", &["y", "o", "b", "o"]);
+    db.run();
+    assert!(derived_by(&db, "thales_converse", "cyclic", &["b", "r", "y", "d"]));
+}
+
+#[test]
+fn isosceles_legs_and_base_angles() {
+    let points = [("a", 0, 4), ("b", -3, 0), ("c", 3, 0)];
+    let mut db = db(&points);
+    add(&mut db, "cong", &["a", "b", "a", "c"]);
+    db.run();
+    assert!(derived_by(&db, "isosceles_base_angles", "eqangle", &["a", "b", "c", "b", "c", "a"]));
+
+    let mut db = self::db(&points);
+    add(&mut db, "eqangle", &["a", "b", "c", "b", "c", "a"]);
+    db.run();
+    assert!(derived_by(&db, "isosceles_equal_legs", "cong", &["a", "b", "a", "c"]));
+}
+
+#[test]
+fn perp_bisector_and_its_converse() {
+    let mut db = db(&[("a", -4, 0), ("b", 4, 0), ("p", 0, 3), ("q", 0, -2)]);
+    add(&mut db, "cong", &["p", "a", "p", "b"]);
+    add(&mut db, "cong", &["q", "a", "q", "b"]);
+    db.run();
+    assert!(derived_by(&db, "perp_bisector", "perp", &["p", "q", "a", "b"]));
+
+    let mut db = self::db(&[("a", -4, 0), ("b", 4, 0), ("p", 0, 3), ("m", 0, 0)]);
+    add(&mut db, "midp", &["m", "a", "b"]);
+    add(&mut db, "perp", &["p", "m", "a", "b"]);
+    db.run();
+    assert!(derived_by(&db, "perp_bisector_cong", "cong", &["p", "a", "p", "b"]));
+}
+
+#[test]
+fn circumcenter_chains_two_radii() {
+    let mut db = db(&[("o", 0, 0), ("a", 5, 0), ("b", 0, 5), ("c", -3, 4)]);
+    add(&mut db, "cong", &["o", "a", "o", "b"]);
+    add(&mut db, "cong", &["o", "b", "o", "c"]);
+    db.run();
+    assert!(derived_by(&db, "circumcenter", "cong", &["o", "a", "o", "c"]));
+}
+
+#[test]
+fn angle_bisector_divides_the_opposite_side() {
+    // ad bisects the right angle at a and meets bc at d
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 0, 4), ("d", 2, 2)]);
+    add(&mut db, "eqangle", &["b", "a", "d", "d", "a", "c"]);
+    add(&mut db, "col", &["b", "d", "c"]);
+    db.run();
+    assert!(derived_by(&db, "angle_bisector", "eqratio", &["d", "b", "d", "c", "a", "b", "a", "c"]));
+}
+
+#[test]
+fn exterior_angle_from_two_remote_angles() {
+    // ∠abc = 40° and ∠cab = 60°, with d on line bc
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3), ("d", -2, 6)]);
+    add(&mut db, "aconst", &["a", "b", "c", "2", "9"]);
+    add(&mut db, "aconst", &["c", "a", "b", "1", "3"]);
+    add(&mut db, "col", &["b", "c", "d"]);
+    db.run();
+    assert!(derived_by(&db, "angle_sum", "aconst", &["a", "c", "d", "5", "9"]));
+    assert!(derived_by(&db, "triangle_angle_sum", "aconst", &["b", "c", "a", "4", "9"]));
+}
+
+#[test]
+fn tangent_facts_reach_the_perp_based_rules() {
+    // Line ut is tangent at p, given without naming p, and the secant from t meets the
+    // circle at a and c
+    let mut db = db(&[("o", 0, 0), ("p", 5, 0), ("u", 5, -3), ("t", 5, 5), ("a", 4, 3), ("c", 0, -5)]);
+    add(&mut db, "tangent", &["u", "t", "o", "p"]);
+    add(&mut db, "cong", &["o", "p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "c"]);
+    add(&mut db, "eqangle", &["t", "p", "a", "p", "c", "a"]);
+    add(&mut db, "col", &["t", "a", "c"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_perp", "perp", &["o", "p", "p", "t"]));
+    assert!(derived_by(&db, "tangent_perp", "perp", &["o", "p", "p", "u"]));
+    assert!(derived_by(&db, "tangent_inscribed_sim", "simtri2", &["t", "p", "a", "t", "c", "p"]));
+
+    // Tangent pt and chord pa: the angle between them is the inscribed angle at c
+    let mut db = self::db(&[("o", 0, 0), ("p", 5, 0), ("t", 5, 5), ("a", 4, 3), ("c", 0, -5)]);
+    add(&mut db, "tangent", &["p", "t", "o", "p"]);
+    add(&mut db, "cong", &["o", "p", "o", "a"]);
+    add(&mut db, "cong", &["o", "p", "o", "c"]);
+    db.run();
+    assert!(derived_by(&db, "tangent_chord", "eqangle", &["t", "p", "a", "p", "c", "a"]));
+}
+
+#[test]
+fn intersecting_chords_have_equal_products() {
+    // Chords ab and cd cross at p: pa·pb = 6·4 = pc·pd = 4√2·3√2
+    let mut db = db(&[("a", -5, 0), ("b", 5, 0), ("c", -3, 4), ("d", 4, -3), ("p", 1, 0)]);
+    add(&mut db, "cyclic", &["a", "b", "c", "d"]);
+    add(&mut db, "col", &["p", "a", "b"]);
+    add(&mut db, "col", &["p", "c", "d"]);
+    db.run();
+    assert!(derived_by(&db, "power_of_point", "eqratio", &["p", "a", "p", "c", "p", "d", "p", "b"]));
+}
+
+#[test]
+fn facts_have_named_fields() {
+    let mut db = db(&[("a", 0, 0), ("b", 1, 1), ("c", 2, 2)]);
+    add(&mut db, "col", &["a", "b", "c"]);
+    add(&mut db, "lconst", &["a", "b", "3", "2"]);
+    db.run();
+    assert!(db.get_facts("nothing").is_empty());
+    let lconst = db.get_facts("lconst");
+    assert!(lconst.iter().any(|f| f.args == strings(&["a", "b", "3", "2"])));
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let col = db.get_facts("col").into_iter().find(|f| f.args == strings(&["a", "b", "c"])).unwrap();
+        let col = Py::new(py, col).unwrap();
+        let col = col.bind(py);
+        assert_eq!(col.getattr("predicate").unwrap().extract::<String>().unwrap(), "col");
+        assert_eq!(col.getattr("args").unwrap().extract::<Vec<String>>().unwrap(), strings(&["a", "b", "c"]));
+        let derivations: Vec<(String, Vec<String>)> = col.getattr("derivations").unwrap().extract().unwrap();
+        assert!(derivations.contains(&("axiom".to_string(), vec![])));
+        assert!(col.repr().unwrap().to_string().starts_with("Fact(col(a, b, c), "));
+    });
+}
+
+#[test]
+fn all_facts_cover_every_relation() {
+    let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3), ("d", 10, 0), ("e", 14, 0), ("f", 11, 3)]);
+    add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+    add(&mut db, "eqangle", &["c", "b", "a", "f", "e", "d"]);
+    add(&mut db, "cong", &["a", "b", "d", "e"]);
+    add(&mut db, "aconst", &["a", "b", "c", "1", "4"]);
+    db.run();
+
+    let total = db.get_col().len() + db.get_para().len() + db.get_perp().len() + db.get_cong().len()
+        + db.get_eqangle().len() + db.get_cyclic().len() + db.get_sameclock().len() + db.get_midp().len()
+        + db.get_contri1().len() + db.get_contri2().len() + db.get_simtri1().len() + db.get_simtri2().len()
+        + db.get_eqratio().len() + db.get_aconst().len() + db.get_lconst().len() + db.get_rconst().len()
+        + db.get_eq().len() + db.get_tangent().len();
+    let all = db.get_all_facts();
+    assert_eq!(all.len(), total);
+    assert!(all.iter().any(|(pred, args, _)| pred == "aconst" && *args == strings(&["a", "b", "c", "1", "4"])));
+}
+
+#[test]
+fn run_without_provenance_finds_the_same_facts() {
+    let build = || {
+        let mut db = db(&[("a", 0, 0), ("b", 4, 0), ("c", 1, 3), ("d", 10, 0), ("e", 14, 0), ("f", 11, 3)]);
+        add(&mut db, "eqangle", &["b", "a", "c", "e", "d", "f"]);
+        add(&mut db, "eqangle", &["c", "b", "a", "f", "e", "d"]);
+        add(&mut db, "cong", &["a", "b", "d", "e"]);
+        db
+    };
+    let facts = |db: &DeductiveDatabase| -> Vec<(String, Vec<String>)> {
+        db.get_all_facts().into_iter().map(|(pred, args, _)| (pred, args)).collect()
+    };
+    // Tuples per relation and derivations recorded overall
+    let counts = |db: &DeductiveDatabase| {
+        let mut tuples: BTreeMap<String, usize> = BTreeMap::new();
+        let mut derivations = 0;
+        for (pred, _, fact_derivations) in db.get_all_facts() {
+            *tuples.entry(pred).or_default() += 1;
+            derivations += fact_derivations.len();
+        }
+        (tuples, derivations)
+    };
+
+    let mut tracked = build();
+    tracked.run();
+    let mut untracked = build();
+    untracked.run_without_provenance();
+
+    assert_eq!(facts(&untracked), facts(&tracked));
+    let ((tracked_tuples, tracked_derivations), (untracked_tuples, untracked_derivations)) = (counts(&tracked), counts(&untracked));
+    assert_eq!(untracked_tuples, tracked_tuples);
+    assert!(tracked_derivations > tracked_tuples.values().sum::<usize>());
+    assert_eq!(untracked_derivations, 0);
+    assert!(untracked.has_contri1("a".into(), "b".into(), "c".into(), "d".into(), "e".into(), "f".into()));
+
+    // The next run records derivations again
+    untracked.run();
+    assert!(untracked.get_all_facts().iter().all(|(.., derivations)| !derivations.is_empty()));
+}
+
+#[test]
+fn get_col_is_the_same_on_every_run() {
+    let solve = || {
+        let mut db = db(&[("a", 0, 0), ("b", 1, 1), ("c", 2, 2), ("d", 3, 3), ("e", 4, 4)]);
+        add(&mut db, "col", &["a", "b", "c"]);
+        add(&mut db, "col", &["a", "b", "d"]);
+        add(&mut db, "col", &["c", "d", "e"]);
+        db.run();
+        db
+    };
+    let (first, second) = (solve(), solve());
+    assert_eq!(format!("{:?}", first.get_col()), format!("{:?}", second.get_col()));
+    assert!(first.get_col().windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn equal_alternate_angles_make_parallels() {
+    // Transversal bc cuts ab and cd at equal alternate angles
+    let mut db = db(&[("a", -4, 0), ("b", 0, 0), ("c", 2, 3), ("d", 6, 3)]);
+    add(&mut db, "eqangle", &["a", "b", "c", "d", "c", "b"]);
+    db.run();
+    assert!(derived_by(&db, "eqangle_para", "para", &["a", "b", "c", "d"]));
+}
+
+#[test]
+fn parallels_make_equal_alternate_angles() {
+    // Two horizontal parallels cut by the slanted transversal bc
+    let mut db = db(&[("a", -4, 0), ("b", 0, 0), ("c", 2, 3), ("d", 6, 3)]);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    db.run();
+    assert!(derived_by(&db, "para_eqangle", "eqangle", &["a", "b", "c", "d", "c", "b"]));
+}
+
+#[test]
+fn self_equal_angle_is_a_right_angle() {
+    let mut db = db(&[("a", 0, 3), ("b", 0, 0), ("c", 4, 0)]);
+    add(&mut db, "eqangle", &["a", "b", "c", "c", "b", "a"]);
+    db.run();
+    assert!(derived_by(&db, "eqangle_perp", "perp", &["b", "a", "b", "c"]));
+}
+
+#[test]
+fn declared_segments_get_trivial_eqratios() {
+    let mut db = db(&[("a", 0, 0), ("b", 3, 0), ("c", 0, 4), ("d", 3, 4)]);
+    add(&mut db, "cong", &["a", "b", "c", "d"]);
+    db.run();
+    assert!(derived_by(&db, "rfl", "eqratio", &["a", "b", "c", "d", "a", "b", "c", "d"]));
+    assert!(derived_by(&db, "rfl", "eqratio", &["c", "d", "a", "b", "c", "d", "a", "b"]));
+    assert!(!derived_by(&db, "rfl", "eqratio", &["a", "c", "b", "d", "a", "c", "b", "d"]));
+    assert!(derived_by(&db, "rfl", "cong", &["a", "c", "a", "c"]));
+    assert!(!db.get_perp().iter().any(|(a, b, c, d, _)| a == c && b == d));
+}
+
+#[test]
+fn contradictions_report_inconsistent_inputs() {
+    let points = [("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2)];
+    let mut db = db(&points);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    db.run();
+    assert!(db.contradictions().is_empty());
+
+    let mut db = self::db(&points);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "perp", &["a", "b", "c", "d"]);
+    add(&mut db, "cong", &["a", "b", "a", "c"]);
+    db.run();
+    let conflicts = db.contradictions();
+    assert!(conflicts.contains(&(fact_id("para", ["a", "b", "c", "d"]), fact_id("perp", ["a", "b", "c", "d"]))));
+    assert!(conflicts.contains(&(fact_id("cong", ["a", "b", "a", "c"]), "coordinates".to_string())));
+}
+
+#[test]
+fn verify_flags_facts_the_coordinates_refute() {
+    let points = [("a", 0, 0), ("b", 4, 0), ("c", 0, 2), ("d", 4, 2), ("e", 0, 5), ("f", 4, 5)];
+    let mut db = db(&points);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "para", &["c", "d", "e", "f"]);
+    db.run();
+    assert!(db.verify().is_empty());
+
+    let mut db = self::db(&points);
+    add(&mut db, "para", &["a", "b", "c", "d"]);
+    add(&mut db, "perp", &["c", "d", "e", "f"]);
+    db.run();
+    let wrong = db.verify();
+    assert!(wrong.contains(&fact_id("perp", ["c", "d", "e", "f"])));
+    assert!(wrong.contains(&fact_id("perp", ["a", "b", "e", "f"])));
+    assert!(!wrong.contains(&fact_id("para", ["a", "b", "c", "d"])));
+}
+
+#[test]
+fn infer_from_coordinates_on_a_unit_square() {
+    let mut db = db(&[("a", 0, 0), ("b", 1, 0), ("c", 1, 1), ("d", 0, 1)]);
+    assert!(db.infer_from_coordinates().unwrap() > 0);
+    db.run();
+    for right_angle in [["a", "b", "a", "d"], ["a", "b", "b", "c"], ["b", "c", "c", "d"], ["a", "d", "c", "d"]] {
+        assert!(derived_by(&db, "coord", "perp", &right_angle));
+    }
+    assert!(db.has_cong("a".into(), "b".into(), "b".into(), "c".into()));
+    assert!(db.has_cong("c".into(), "d".into(), "d".into(), "a".into()));
+    assert!(db.has_cong("a".into(), "c".into(), "b".into(), "d".into()));
+    assert!(!db.has_cong("a".into(), "b".into(), "a".into(), "c".into()));
+    assert!(db.verify().is_empty());
+}
+
+#[test]
+fn near_collinear_points_depend_on_each_database_epsilon() {
+    let float_db = |eps: f64| {
+        let mut db = DeductiveDatabase::new(Some(eps)).unwrap();
+        for (name, x, y) in [("a", 0.0, 0.0), ("b", 1.0, 0.001), ("c", 2.0, 0.0)] {
+            db.add_point(Coord::Float(x), Coord::Float(y), name.to_string()).unwrap();
+        }
+        db.infer_from_coordinates().unwrap();
+        db
+    };
+    let is_col = |db: &DeductiveDatabase| db.numeric_value("col", &strings(&["a", "b", "c"]));
+
+    // b is 0.001 off ac: collinear within 0.01, a proper triangle within 1e-6
+    let mut loose = float_db(0.01);
+    let mut tight = float_db(1e-6);
+    assert_eq!(is_col(&loose), Some(true));
+    assert_eq!(is_col(&tight), Some(false));
+    tight.run();
+    loose.run();
+    assert!(loose.has_col("a".into(), "b".into(), "c".into()));
+    assert!(!tight.has_col("a".into(), "b".into(), "c".into()));
+    assert_eq!(is_col(&tight), Some(false));
+
+    // Integer coordinates are exact: one unit off a long base is never collinear
+    let exact = db(&[("a", 0, 0), ("b", 200_000, 1), ("c", 400_000, 0)]);
+    assert_eq!(is_col(&exact), Some(false));
+}